@@ -0,0 +1,134 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ls_enhanced::{
+    config::{create_config, Color, Config},
+    theme::Theme,
+    types::files::{get_file_type, get_file_type_glyph, render_file},
+};
+use std::collections::HashMap;
+
+const FILE_NAMES: [&str; 10] = [
+    "main.rs",
+    "index.ts",
+    "style.scss",
+    "README.md",
+    "Cargo.toml",
+    "photo.png",
+    "song.flac",
+    "build.zig",
+    "settings.yml",
+    "unknown.xyz",
+];
+
+/// The colors rendering a file used to resolve, all of them for each file
+const OLD_COLOR_KEYS: [&str; 35] = [
+    "rust",
+    "config",
+    "c",
+    "cpp",
+    "cs",
+    "zig",
+    "python",
+    "javascript",
+    "typescript",
+    "html",
+    "css",
+    "scss",
+    "less",
+    "react",
+    "git",
+    "lock",
+    "toml",
+    "license",
+    "markdown",
+    "golang",
+    "svg",
+    "photo",
+    "audio",
+    "video",
+    "blender",
+    "lua",
+    "vim",
+    "gleam",
+    "php",
+    "json",
+    "yaml",
+    "kotlin",
+    "java",
+    "assembly",
+    "twig",
+];
+
+/// A directory of `count` files
+fn file_names(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| format!("{}_{}", i, FILE_NAMES[i % FILE_NAMES.len()]))
+        .collect()
+}
+
+/// How a color used to be resolved: the defaults were rebuilt with `create_config()` once per
+/// channel, whether the config had the color or not
+fn old_color(config: &Config, key: &str) -> Option<Color> {
+    let mut color = None;
+    for _channel in 0..3 {
+        let default = create_config().colors.get(key).copied();
+        color = config.colors.get(key).copied().or(default);
+    }
+    color
+}
+
+fn bench_theme(c: &mut Criterion) {
+    let config = create_config();
+    let names = file_names(10_000);
+
+    c.bench_function("theme: build once", |b| {
+        b.iter(|| Theme::new(black_box(&config)))
+    });
+
+    c.bench_function("10k files: theme lookup", |b| {
+        b.iter(|| {
+            let theme = Theme::new(&config);
+            for name in &names {
+                let file_type = get_file_type(name.to_string());
+                black_box(render_file(name.to_string(), file_type, &theme));
+            }
+        })
+    });
+
+    // what rendering a file used to cost: 3 × 35 `create_config()` calls for each file, too slow
+    // for 10k files
+    let names = file_names(100);
+    let mut group = c.benchmark_group("100 files");
+    group.sample_size(10);
+
+    group.bench_function("colors resolved per file", |b| {
+        b.iter(|| {
+            for name in &names {
+                let file_type = get_file_type(name.to_string());
+                let colors: HashMap<&str, Color> = OLD_COLOR_KEYS
+                    .iter()
+                    .filter_map(|key| Some((*key, old_color(&config, key)?)))
+                    .collect();
+                let icon = match colors.get(file_type.config_key()) {
+                    Some(color) => color.paint(get_file_type_glyph(file_type)),
+                    None => get_file_type_glyph(file_type).to_string(),
+                };
+                black_box(format!("{} {}", icon, name));
+            }
+        })
+    });
+
+    group.bench_function("theme lookup", |b| {
+        b.iter(|| {
+            let theme = Theme::new(&config);
+            for name in &names {
+                let file_type = get_file_type(name.to_string());
+                black_box(render_file(name.to_string(), file_type, &theme));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_theme);
+criterion_main!(benches);
@@ -1,10 +1,29 @@
-#[derive(PartialEq)]
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Arguments {
     All,
     List,
     Recursive,
     Icon,
     Help,
+    Color,
+    Columns,
+    Across,
+    Hyperlink,
+    QuotingStyle,
+    Zero,
+    Json,
+    Theme,
+    ExportLsColors,
+    Sniff,
+    Classify,
+    IndicatorStyle,
+    Sort,
+    Only,
+    Mime,
+    Image,
+    Media,
 }
 
 #[derive(PartialEq)]
@@ -59,13 +78,18 @@ impl Argument {
         self
     }
 
-    pub fn get_arg_type(self: &Self) -> &Arguments {
+    pub fn get_arg_type(&self) -> &Arguments {
         if let Some(arg) = &self.arg_type {
             arg
         } else {
             &Arguments::Help
         }
     }
+
+    /// Checks if `arg` (without any `=value` part) is the short or long form of this argument
+    pub fn matches(&self, arg: &str) -> bool {
+        self.short.as_deref() == Some(arg) || self.long.as_deref() == Some(arg)
+    }
 }
 
 pub fn create_arg() -> Vec<Argument> {
@@ -80,7 +104,7 @@ pub fn create_arg() -> Vec<Argument> {
     let list = Argument::new("list")
         .set_short("l")
         .set_long("list")
-        .set_description("List the files with their read and write properties")
+        .set_description("List the files with their permissions, size and modification time")
         .set_arg_type(Arguments::List);
 
     // -h or --help
@@ -90,11 +114,11 @@ pub fn create_arg() -> Vec<Argument> {
         .set_description("Lists all possible arguments")
         .set_arg_type(Arguments::Help);
 
-    // -i=bool or --icon=bool
+    // -i or --icon=nerd|emoji|ascii|none
     let icon = Argument::new("icon")
         .set_short("i")
         .set_long("icon")
-        .set_description("show or not the icons")
+        .set_description("Show the icons, from a set: nerd, emoji, ascii or none")
         .set_arg_type(Arguments::Icon);
 
     // -r or --recursive
@@ -104,11 +128,180 @@ pub fn create_arg() -> Vec<Argument> {
         .set_description("See all the files as a tree")
         .set_arg_type(Arguments::Recursive);
 
-    let possible_args: Vec<Argument> = vec![all, list, help, icon, recursive];
+    // --color=auto|always|never
+    let color = Argument::new("color")
+        .set_long("color")
+        .set_description("When to use colors: auto, always or never")
+        .set_default_value("always")
+        .set_arg_type(Arguments::Color);
+
+    // -C or --columns
+    let columns = Argument::new("columns")
+        .set_short("C")
+        .set_long("columns")
+        .set_description("List the files in columns, sorted downwards")
+        .set_arg_type(Arguments::Columns);
+
+    // -x or --across
+    let across = Argument::new("across")
+        .set_short("x")
+        .set_long("across")
+        .set_description("List the files in columns, sorted across the rows")
+        .set_arg_type(Arguments::Across);
+
+    // --hyperlink=auto|always|never
+    let hyperlink = Argument::new("hyperlink")
+        .set_long("hyperlink")
+        .set_description("Make the file names clickable links: auto, always or never")
+        .set_default_value("always")
+        .set_arg_type(Arguments::Hyperlink);
+
+    // --quoting-style=literal|shell|shell-escape|c|escape
+    let quoting_style = Argument::new("quoting-style")
+        .set_long("quoting-style")
+        .set_description("How to quote file names: literal, shell, shell-escape, c or escape")
+        .set_arg_type(Arguments::QuotingStyle);
+
+    // --zero
+    let zero = Argument::new("zero")
+        .set_long("zero")
+        .set_description("Print the raw file names separated by NUL characters, for scripts")
+        .set_arg_type(Arguments::Zero);
+
+    // --json
+    let json = Argument::new("json")
+        .set_long("json")
+        .set_description("Print one JSON object per file, for scripts")
+        .set_arg_type(Arguments::Json);
+
+    // --theme=NAME
+    let theme = Argument::new("theme")
+        .set_long("theme")
+        .set_description("Use a built-in theme or one from ~/.config/lse/themes/")
+        .set_arg_type(Arguments::Theme);
+
+    // --export-ls-colors
+    let export_ls_colors = Argument::new("export-ls-colors")
+        .set_long("export-ls-colors")
+        .set_description("Print the colors of the theme as an LS_COLORS value")
+        .set_arg_type(Arguments::ExportLsColors);
+
+    // --sniff
+    let sniff = Argument::new("sniff")
+        .set_long("sniff")
+        .set_description("Guess the type of files without extension from their first bytes")
+        .set_arg_type(Arguments::Sniff);
+
+    // -F or --classify
+    let classify = Argument::new("classify")
+        .set_short("F")
+        .set_long("classify")
+        .set_description(
+            "Append an indicator to names: / for directories, * for executables, @, | and =",
+        )
+        .set_arg_type(Arguments::Classify);
+
+    // --indicator-style=none|slash|file-type|classify
+    let indicator_style = Argument::new("indicator-style")
+        .set_long("indicator-style")
+        .set_description("Which indicators to append to names: none, slash, file-type or classify")
+        .set_arg_type(Arguments::IndicatorStyle);
+
+    // --sort=none|name|type|category
+    let sort = Argument::new("sort")
+        .set_long("sort")
+        .set_description("Sort the files: none, name, type or category")
+        .set_arg_type(Arguments::Sort);
+
+    // --only=CATEGORY,TYPE
+    let only = Argument::new("only")
+        .set_long("only")
+        .set_description("Only list the files of these categories or types, like media,rust")
+        .set_arg_type(Arguments::Only);
+
+    // --mime
+    let mime = Argument::new("mime")
+        .set_long("mime")
+        .set_description("List the files with their MIME type")
+        .set_arg_type(Arguments::Mime);
+
+    // --image
+    let image = Argument::new("image")
+        .set_long("image")
+        .set_description("List images with their dimensions, and photos with their date and camera")
+        .set_arg_type(Arguments::Image);
+
+    // --media
+    let media = Argument::new("media")
+        .set_long("media")
+        .set_description(
+            "List audio and video files with their duration, bitrate or resolution and codec",
+        )
+        .set_arg_type(Arguments::Media);
+
+    let possible_args: Vec<Argument> = vec![
+        all,
+        list,
+        help,
+        icon,
+        recursive,
+        color,
+        columns,
+        across,
+        hyperlink,
+        quoting_style,
+        zero,
+        json,
+        theme,
+        export_ls_colors,
+        sniff,
+        classify,
+        indicator_style,
+        sort,
+        only,
+        mime,
+        image,
+        media,
+    ];
 
     possible_args
 }
 
+/// Splits the command line into the directories to list and the recognized arguments,
+/// together with the value given through `--arg=value` (or the argument's default value)
+pub fn parse_args(
+    raw_args: impl IntoIterator<Item = String>,
+) -> (Vec<String>, Vec<(Arguments, Option<String>)>) {
+    let possible_args = create_arg();
+    let mut args: Vec<(Arguments, Option<String>)> = Vec::new();
+    let mut paths: Vec<String> = Vec::new();
+
+    for arg in raw_args.into_iter().skip(1) {
+        // anything that isn't an option is a path to list, even if it doesn't exist, so
+        // that it gets reported instead of silently listing the current directory
+        if !arg.starts_with('-') || Path::new(&arg).is_dir() {
+            paths.push(arg);
+            continue;
+        }
+
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+
+        for possible_arg in &possible_args {
+            if possible_arg.matches(name) {
+                let arg_type = *possible_arg.get_arg_type();
+                let value = value.clone().or(possible_arg.default_value.clone());
+                args.retain(|(existing, _)| *existing != arg_type);
+                args.push((arg_type, value));
+            }
+        }
+    }
+
+    (paths, args)
+}
+
 pub fn generate_help_text() -> std::io::Result<()> {
     let args = create_arg();
 
@@ -116,9 +309,9 @@ pub fn generate_help_text() -> std::io::Result<()> {
         println!(
             "Name: {}\nShorthand: {}\nLonghand: {}\nDescription: {}\n",
             arg.name,
-            arg.short.unwrap(),
-            arg.long.unwrap(),
-            arg.description.unwrap(),
+            arg.short.unwrap_or_default(),
+            arg.long.unwrap_or_default(),
+            arg.description.unwrap_or_default(),
         );
     }
 
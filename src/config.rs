@@ -1,4 +1,9 @@
-use colored::Colorize;
+use crate::formatting::{
+    color::color_support, indicator::IndicatorStyle, quoting::QuotingStyle, render::ColorNames,
+};
+use crate::ls_colors::LsColorsMode;
+use crate::palette::{ColorSupport, ColorValue, ANSI_NAMES};
+use crate::types::{files::FileType, icons::IconSet, rules::TypeRule};
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::File;
@@ -7,9 +12,50 @@ use std::io::Write;
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Format {
     pub icons: bool,
+    /// The glyphs of the icons, Nerd Font ones when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_set: Option<IconSet>,
     pub inline: bool,
     pub dotfiles: bool,
     pub colors: bool,
+    /// What the names of regular files are colored by, independently of the icons
+    #[serde(default)]
+    pub color_names: ColorNames,
+    #[serde(default)]
+    pub hyperlink: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quoting_style: Option<QuotingStyle>,
+    /// The suffix after names, like `/` for directories
+    #[serde(default)]
+    pub indicator_style: IndicatorStyle,
+    /// A built-in theme, or one from `~/.config/lse/themes/`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// How `LS_COLORS` combines with the colors of the config and theme
+    #[serde(default)]
+    pub ls_colors: LsColorsMode,
+    /// A `dircolors` database read before `LS_COLORS`, when `ls_colors` isn't `ignore`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dircolors: Option<String>,
+    /// Guess the type of files without extension from their first bytes
+    #[serde(default)]
+    pub sniff: bool,
+    /// Give files with an unknown extension a color picked from a hash of the extension
+    #[serde(default)]
+    pub auto_color_unknown: bool,
+    /// Background of the terminal, the automatic colors are made readable on it (black when
+    /// neither this nor the theme says)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+}
+
+/// Icon and color of the directories matching a name or glob, both optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DirectoryStyle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,11 +64,142 @@ pub struct Plugin {
     pub colors: HashMap<String, Color>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A color of the config or of a theme, along with its style attributes.
+///
+/// Written as `"#ce422b"`, `"#c42"`, a CSS or ANSI name like `"tomato"` or `"bright-red"`, a
+/// palette index like `208`, or a table like `{ fg = "#ce422b", bg = "black", bold = true }`
+/// (`{ red, green, blue }` tables work too).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(try_from = "RawColor", into = "RawColor")]
 pub struct Color {
-    pub red: u8,
-    pub green: u8,
-    pub blue: u8,
+    pub foreground: Option<ColorValue>,
+    pub background: Option<ColorValue>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+/// A color value as written in TOML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawValue {
+    Index(u8),
+    Text(String),
+}
+
+/// Every way a `Color` can be written in TOML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawColor {
+    Value(RawValue),
+    Table(ColorTable),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColorTable {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    red: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    green: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    blue: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fg: Option<RawValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bg: Option<RawValue>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    bold: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    italic: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    underline: bool,
+}
+
+impl TryFrom<RawValue> for ColorValue {
+    type Error = String;
+
+    fn try_from(raw: RawValue) -> Result<Self, Self::Error> {
+        match raw {
+            RawValue::Index(index) => Ok(ColorValue::Fixed(index)),
+            RawValue::Text(text) => ColorValue::parse(&text),
+        }
+    }
+}
+
+impl From<ColorValue> for RawValue {
+    fn from(value: ColorValue) -> Self {
+        match value {
+            ColorValue::Ansi(index) => RawValue::Text(ANSI_NAMES[index as usize % 16].to_string()),
+            ColorValue::Fixed(index) => RawValue::Index(index),
+            ColorValue::Rgb(red, green, blue) => {
+                RawValue::Text(format!("#{:02x}{:02x}{:02x}", red, green, blue))
+            }
+        }
+    }
+}
+
+impl TryFrom<RawColor> for Color {
+    type Error = String;
+
+    fn try_from(raw: RawColor) -> Result<Self, Self::Error> {
+        let table = match raw {
+            RawColor::Value(value) => return Ok(Color::from(ColorValue::try_from(value)?)),
+            RawColor::Table(table) => table,
+        };
+
+        let foreground = match (table.fg, table.red, table.green, table.blue) {
+            (Some(_), Some(_), _, _) | (Some(_), _, Some(_), _) | (Some(_), _, _, Some(_)) => {
+                return Err("a color can't have both `fg` and `red`, `green`, `blue`".to_string())
+            }
+            (Some(fg), None, None, None) => Some(ColorValue::try_from(fg)?),
+            (None, None, None, None) => None,
+            (None, red, green, blue) => Some(ColorValue::Rgb(
+                red.unwrap_or(0),
+                green.unwrap_or(0),
+                blue.unwrap_or(0),
+            )),
+        };
+
+        Ok(Color {
+            foreground,
+            background: table.bg.map(ColorValue::try_from).transpose()?,
+            bold: table.bold,
+            italic: table.italic,
+            underline: table.underline,
+        })
+    }
+}
+
+impl From<Color> for RawColor {
+    fn from(color: Color) -> Self {
+        match color {
+            Color {
+                foreground: Some(foreground),
+                background: None,
+                bold: false,
+                italic: false,
+                underline: false,
+            } => RawColor::Value(foreground.into()),
+            _ => RawColor::Table(ColorTable {
+                fg: color.foreground.map(RawValue::from),
+                bg: color.background.map(RawValue::from),
+                bold: color.bold,
+                italic: color.italic,
+                underline: color.underline,
+                ..ColorTable::default()
+            }),
+        }
+    }
+}
+
+impl From<ColorValue> for Color {
+    fn from(value: ColorValue) -> Self {
+        Color {
+            foreground: Some(value),
+            ..Color::default()
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,15 +207,47 @@ pub struct Config {
     pub format: Format,
     pub plugins: HashMap<String, Plugin>,
     pub colors: HashMap<String, Color>,
+    /// Icons replacing the ones of the icon set, same keys as `colors` plus `directory`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub icons: HashMap<String, String>,
+    /// Styles of directories by name or glob, like `node_modules` or `*.egg-info`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub directories: HashMap<String, DirectoryStyle>,
+    /// Colors of whole categories, like `media`, below the ones of single file types
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub categories: HashMap<String, Color>,
+    /// File type rules tried before the built-in ones, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<TypeRule>,
 }
 
 impl Format {
     pub fn new(icons: bool, inline: bool, dotfiles: bool, colors: bool) -> Format {
         Format {
             icons,
+            icon_set: None,
             inline,
             dotfiles,
             colors,
+            color_names: ColorNames::Type,
+            hyperlink: false,
+            quoting_style: None,
+            indicator_style: IndicatorStyle::None,
+            theme: None,
+            ls_colors: LsColorsMode::Ignore,
+            dircolors: None,
+            sniff: false,
+            auto_color_unknown: false,
+            background: None,
+        }
+    }
+
+    /// The icon set in use, `IconSet::None` when icons are disabled
+    pub fn icon_set(&self) -> IconSet {
+        if self.icons {
+            self.icon_set.unwrap_or_default()
+        } else {
+            IconSet::None
         }
     }
 }
@@ -51,79 +260,87 @@ impl Plugin {
 
 impl Color {
     pub fn new(red: u8, green: u8, blue: u8) -> Color {
-        Color { red, green, blue }
+        Color::from(ColorValue::Rgb(red, green, blue))
+    }
+
+    pub fn set_background(mut self, background: ColorValue) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    pub fn set_bold(mut self, bold: bool) -> Self {
+        self.bold = bold;
+        self
+    }
+
+    /// `text` in this color, downgraded to what the terminal supports, or as is when colors
+    /// are disabled
+    pub fn paint(&self, text: &str) -> String {
+        self.paint_with(text, color_support())
+    }
+
+    /// Same as `paint`, for a given terminal
+    pub fn paint_with(&self, text: &str, support: ColorSupport) -> String {
+        if !colored::control::SHOULD_COLORIZE.should_colorize() {
+            return text.to_string();
+        }
+
+        let parameters = self.sgr(support);
+
+        if parameters.is_empty() {
+            text.to_string()
+        } else {
+            format!("\x1b[{}m{}\x1b[0m", parameters, text)
+        }
+    }
+
+    /// The SGR parameters of the color and attributes, like `1;38;5;208`
+    pub fn sgr(&self, support: ColorSupport) -> String {
+        let mut parameters: Vec<String> = Vec::new();
+
+        for (enabled, parameter) in [(self.bold, "1"), (self.italic, "3"), (self.underline, "4")] {
+            if enabled {
+                parameters.push(parameter.to_string());
+            }
+        }
+        if let Some(foreground) = self.foreground {
+            parameters.push(foreground.downgrade(support).sgr(false));
+        }
+        if let Some(background) = self.background {
+            parameters.push(background.downgrade(support).sgr(true));
+        }
+
+        parameters.join(";")
     }
 }
 
+/// The color of every file type, from the config, its category in the config, or the defaults.
+///
+/// This is meant to be resolved once (see `Theme`), not for every file.
 pub fn get_config_colors(config: &Config) -> HashMap<String, Color> {
+    let defaults = create_config();
     let mut result: HashMap<String, Color> = HashMap::new();
-    let config_colors: Vec<&str> = vec![
-        "rust",
-        "config",
-        "c",
-        "cpp",
-        "cs",
-        "zig",
-        "python",
-        "javascript",
-        "typescript",
-        "html",
-        "css",
-        "scss",
-        "less",
-        "react",
-        "git",
-        "lock",
-        "toml",
-        "license",
-        "markdown",
-        "golang",
-        "svg",
-        "photo",
-        "audio",
-        "video",
-        "blender",
-        "lua",
-        "vim",
-        "gleam",
-        "php",
-        "json",
-        "yaml",
-        "kotlin",
-        "java",
-        "assembly",
-        "twig",
-    ];
-
-    for config_color in config_colors {
-        result.insert(config_color.to_string(), get_colors(&config, config_color));
-    }
 
-    result
-}
+    for file_type in FileType::ALL {
+        let key = file_type.config_key();
 
-pub fn get_colors(config: &Config, config_color: &str) -> Color {
-    Color {
-        red: config
-            .colors
-            .get(config_color)
-            .unwrap_or(create_config().colors.get(config_color).unwrap())
-            .red,
-        green: config
-            .colors
-            .get(config_color)
-            .unwrap_or(create_config().colors.get(config_color).unwrap())
-            .green,
-        blue: config
+        // configs written before a file type had its own key keep the color of the old one
+        let legacy = file_type
+            .legacy_key()
+            .and_then(|legacy| config.colors.get(legacy));
+
+        if let Some(color) = config
             .colors
-            .get(config_color)
-            .unwrap_or(create_config().colors.get(config_color).unwrap())
-            .blue,
+            .get(key)
+            .or(legacy)
+            .or(config.categories.get(file_type.category().config_key()))
+            .or(defaults.colors.get(key))
+        {
+            result.insert(key.to_string(), *color);
+        }
     }
-}
 
-pub fn set_truecolor(file_type_icon: &str, color: &Color) -> colored::ColoredString {
-    file_type_icon.truecolor(color.red, color.green, color.blue)
+    result
 }
 
 pub fn create_config() -> Config {
@@ -143,7 +360,8 @@ pub fn create_config() -> Config {
 
     let colors = HashMap::from([
         ("rust".to_string(), Color::new(206, 66, 43)),
-        ("config".to_string(), Color::new(128, 128, 128)),
+        ("config".to_string(), Color::new(111, 143, 175)),
+        ("unknown".to_string(), Color::new(128, 128, 128)),
         ("c".to_string(), Color::new(57, 74, 171)),
         ("cpp".to_string(), Color::new(0, 89, 156)),
         ("cs".to_string(), Color::new(149, 60, 173)),
@@ -177,17 +395,70 @@ pub fn create_config() -> Config {
         ("java".to_string(), Color::new(234, 45, 46)),
         ("assembly".to_string(), Color::new(37, 52, 76)),
         ("twig".to_string(), Color::new(160, 203, 41)),
+        ("docker".to_string(), Color::new(36, 150, 237)),
+        ("make".to_string(), Color::new(109, 128, 134)),
+        ("archive".to_string(), Color::new(229, 166, 0)),
+        ("shell".to_string(), Color::new(137, 224, 81)),
+        ("pdf".to_string(), Color::new(236, 28, 36)),
+        ("text".to_string(), Color::new(204, 204, 204)),
+        ("binary".to_string(), Color::new(78, 201, 176)),
+        ("ruby".to_string(), Color::new(204, 52, 45)),
+        ("swift".to_string(), Color::new(240, 81, 56)),
+        ("dart".to_string(), Color::new(0, 180, 171)),
+        ("elixir".to_string(), Color::new(110, 74, 126)),
+        ("erlang".to_string(), Color::new(184, 57, 152)),
+        ("haskell".to_string(), Color::new(94, 80, 134)),
+        ("scala".to_string(), Color::new(220, 50, 47)),
+        ("perl".to_string(), Color::new(57, 69, 126)),
+        ("r".to_string(), Color::new(39, 109, 195)),
+        ("julia".to_string(), Color::new(149, 88, 178)),
+        ("ocaml".to_string(), Color::new(238, 106, 26)),
+        ("clojure".to_string(), Color::new(99, 177, 50)),
+        ("nim".to_string(), Color::new(255, 233, 83)),
+        ("nix".to_string(), Color::new(126, 186, 228)),
+        ("terraform".to_string(), Color::new(132, 79, 186)),
+        ("sql".to_string(), Color::new(218, 165, 32)),
+        ("protobuf".to_string(), Color::new(66, 133, 244)),
+        ("graphql".to_string(), Color::new(225, 0, 152)),
+        ("svelte".to_string(), Color::new(255, 62, 0)),
+        ("vue".to_string(), Color::new(65, 184, 131)),
+        ("font".to_string(), Color::new(236, 236, 236)),
+        ("csv".to_string(), Color::new(33, 163, 102)),
+        ("xml".to_string(), Color::new(227, 121, 51)),
+        ("tex".to_string(), Color::new(0, 128, 128)),
+        ("document".to_string(), Color::new(43, 87, 154)),
+        ("spreadsheet".to_string(), Color::new(33, 115, 70)),
+        ("presentation".to_string(), Color::new(210, 71, 38)),
+        ("ebook".to_string(), Color::new(166, 118, 84)),
+        ("database".to_string(), Color::new(240, 200, 80)),
+        ("diff".to_string(), Color::new(65, 179, 132)),
+        ("key".to_string(), Color::new(230, 200, 50)),
     ]);
 
     Config {
         format: Format {
             icons: true,
+            icon_set: Some(IconSet::Nerd),
             inline: true,
             dotfiles: true,
             colors: true,
+            color_names: ColorNames::Type,
+            hyperlink: false,
+            quoting_style: None,
+            indicator_style: IndicatorStyle::None,
+            theme: None,
+            ls_colors: LsColorsMode::Ignore,
+            dircolors: None,
+            sniff: false,
+            auto_color_unknown: false,
+            background: None,
         },
         plugins,
         colors,
+        icons: HashMap::new(),
+        directories: HashMap::new(),
+        categories: HashMap::new(),
+        rules: Vec::new(),
     }
 }
 
@@ -196,7 +467,7 @@ pub fn generate_config(path: String) -> std::io::Result<()> {
     let toml_string = toml::to_string(config).expect("Failed to serialize config");
 
     let mut file = File::create(&path)?;
-    let _ = file.write_all(toml_string.as_bytes())?;
+    file.write_all(toml_string.as_bytes())?;
 
     Ok(())
 }
@@ -0,0 +1,109 @@
+use serde_derive::{Deserialize, Serialize};
+use std::env;
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::palette::ColorSupport;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorWhen {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorWhen {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "auto" | "tty" | "if-tty" => Ok(ColorWhen::Auto),
+            "always" | "yes" | "force" => Ok(ColorWhen::Always),
+            "never" | "no" | "none" => Ok(ColorWhen::Never),
+            _ => Err(format!("invalid argument '{}'", value)),
+        }
+    }
+}
+
+fn env_is_set(name: &str) -> bool {
+    env::var_os(name).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Resolves `when` for features that only depend on stdout being a terminal (like hyperlinks)
+pub fn when_stdout_is_terminal(when: ColorWhen) -> bool {
+    match when {
+        ColorWhen::Always => true,
+        ColorWhen::Never => false,
+        ColorWhen::Auto => io::stdout().is_terminal(),
+    }
+}
+
+/// Resolves `when` into a yes/no answer.
+///
+/// `auto` follows the usual conventions: `NO_COLOR` disables colors, `CLICOLOR_FORCE`
+/// (anything but `0`) forces them, and otherwise colors are only used when stdout is a terminal.
+pub fn should_colorize(when: ColorWhen) -> bool {
+    match when {
+        ColorWhen::Always => true,
+        ColorWhen::Never => false,
+        ColorWhen::Auto => {
+            if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+                false
+            } else if env_is_set("CLICOLOR_FORCE") {
+                true
+            } else {
+                io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+/// Makes the color decision once for the whole program, every renderer going through `colored`
+/// will then follow it.
+pub fn apply_color_choice(when: ColorWhen) -> bool {
+    let enabled = should_colorize(when);
+    colored::control::set_override(enabled);
+    enabled
+}
+
+/// Terminals known to show 24-bit colors even when `COLORTERM` doesn't say so
+const TRUECOLOR_TERMS: [&str; 6] = [
+    "xterm-kitty",
+    "xterm-ghostty",
+    "alacritty",
+    "wezterm",
+    "foot",
+    "contour",
+];
+
+/// Guesses what the terminal supports from `COLORTERM` and `TERM`.
+///
+/// Without `TERM`, stdout is most likely not a terminal and colors are kept exact.
+pub fn detect_color_support(colorterm: Option<&str>, term: Option<&str>) -> ColorSupport {
+    if matches!(colorterm, Some("truecolor" | "24bit")) {
+        return ColorSupport::TrueColor;
+    }
+
+    match term {
+        None | Some("") => ColorSupport::TrueColor,
+        Some(term) if term.ends_with("-direct") || TRUECOLOR_TERMS.contains(&term) => {
+            ColorSupport::TrueColor
+        }
+        Some(term) if term.contains("256color") => ColorSupport::Ansi256,
+        Some(_) => ColorSupport::Ansi16,
+    }
+}
+
+/// What the terminal supports, looked up once
+pub fn color_support() -> ColorSupport {
+    static SUPPORT: OnceLock<ColorSupport> = OnceLock::new();
+    *SUPPORT.get_or_init(|| {
+        detect_color_support(
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    })
+}
@@ -0,0 +1,97 @@
+use std::io;
+use std::path::Path;
+
+/// Exit code when everything went fine
+pub const EXIT_OK: i32 = 0;
+/// Exit code for minor problems, like an entry that couldn't be read
+pub const EXIT_MINOR: i32 = 1;
+/// Exit code for serious trouble, like a path given on the command line that couldn't be listed
+pub const EXIT_SERIOUS: i32 = 2;
+
+/// Collects the errors met while listing, so one bad entry doesn't abort the whole listing.
+///
+/// Every error is printed to stderr right away, GNU ls style (unless created with
+/// `Errors::quiet`), and the exit code is the worst severity seen.
+#[derive(Debug, Default)]
+pub struct Errors {
+    exit_code: i32,
+    messages: Vec<String>,
+    quiet: bool,
+}
+
+/// The message of an IO error without the "(os error N)" suffix
+pub fn describe(error: &io::Error) -> String {
+    let message = error.to_string();
+
+    match message.find(" (os error") {
+        Some(index) => message[..index].to_string(),
+        None => message,
+    }
+}
+
+impl Errors {
+    pub fn new() -> Errors {
+        Errors {
+            exit_code: EXIT_OK,
+            messages: Vec::new(),
+            quiet: false,
+        }
+    }
+
+    /// Only collects the messages, for when ls-enhanced is used as a library
+    pub fn quiet() -> Errors {
+        Errors {
+            quiet: true,
+            ..Errors::new()
+        }
+    }
+
+    fn report(&mut self, exit_code: i32, action: &str, path: &Path, error: &io::Error) {
+        let message = format!("{} '{}': {}", action, path.display(), describe(error));
+
+        if !self.quiet {
+            eprintln!("lse: {}", message);
+        }
+
+        self.messages.push(message);
+        self.exit_code = self.exit_code.max(exit_code);
+    }
+
+    /// `lse: cannot access 'path': ...`, for an entry inside a listed directory
+    pub fn cannot_access(&mut self, path: &Path, error: &io::Error) {
+        self.report(EXIT_MINOR, "cannot access", path, error);
+    }
+
+    /// `lse: cannot access 'path': ...`, for a path given on the command line
+    pub fn cannot_access_operand(&mut self, path: &Path, error: &io::Error) {
+        self.report(EXIT_SERIOUS, "cannot access", path, error);
+    }
+
+    /// `lse: cannot open directory 'path': ...`, for a subdirectory met while recursing
+    pub fn cannot_open_subdirectory(&mut self, path: &Path, error: &io::Error) {
+        self.report(EXIT_MINOR, "cannot open directory", path, error);
+    }
+
+    /// `lse: cannot open directory 'path': ...`, for a path given on the command line
+    pub fn cannot_open_directory(&mut self, path: &Path, error: &io::Error) {
+        self.report(EXIT_SERIOUS, "cannot open directory", path, error);
+    }
+
+    /// `lse: error reading directory 'path': ...`
+    pub fn cannot_read_directory(&mut self, path: &Path, error: &io::Error) {
+        self.report(EXIT_MINOR, "reading directory", path, error);
+    }
+
+    /// `lse: cannot read archive 'path': ...`, for an archive given on the command line
+    pub fn cannot_read_archive(&mut self, path: &Path, error: &io::Error) {
+        self.report(EXIT_SERIOUS, "cannot read archive", path, error);
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code
+    }
+
+    pub fn messages(&self) -> &[String] {
+        &self.messages
+    }
+}
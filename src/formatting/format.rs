@@ -1,243 +1,235 @@
 use crate::{
     arguments::{generate_help_text, Arguments},
     config::Config,
-    formatting::permissions::{format_permissions, read_permission},
-    types::files,
+    formatting::{
+        color::{apply_color_choice, color_support, when_stdout_is_terminal, ColorWhen},
+        errors::{describe, Errors, EXIT_OK},
+        grid::GridDirection,
+        indicator::IndicatorStyle,
+        quoting::QuotingStyle,
+    },
+    lister::{Lister, SortKey, View},
+    ls_colors::{export_ls_colors, LsColors, LsColorsMode},
+    palette::ColorValue,
+    theme::{load_theme_file, Theme},
+    types::{files::TypeSelector, icons::IconSet},
 };
-use colored::Colorize;
-use std::cmp;
-use std::fs;
-use term_size;
-
-pub fn format_ls(mut config: Config, path: String, args: Vec<&Arguments>) -> std::io::Result<()> {
+use std::io;
+use std::process::exit;
+
+/// Lists `paths` according to the config and arguments, and returns the exit code
+pub fn format_ls(
+    mut config: Config,
+    paths: Vec<String>,
+    args: Vec<(Arguments, Option<String>)>,
+) -> i32 {
     let mut show_permissions: bool = false;
+    let mut show_mime: bool = false;
+    let mut show_image: bool = false;
+    let mut show_media: bool = false;
     let mut recursive: bool = false;
+    let mut direction: GridDirection = GridDirection::Down;
+    let mut zero: bool = false;
+    let mut json: bool = false;
+    let mut export: bool = false;
+    let mut sort: SortKey = SortKey::None;
+    let mut only: Vec<TypeSelector> = Vec::new();
+    let mut color_when: ColorWhen = if config.format.colors {
+        ColorWhen::Auto
+    } else {
+        ColorWhen::Never
+    };
+    let mut hyperlink_when: ColorWhen = if config.format.hyperlink {
+        ColorWhen::Auto
+    } else {
+        ColorWhen::Never
+    };
 
-    for arg in args {
-        let _ = match arg {
-            &Arguments::All => {
+    for (arg, value) in args {
+        match arg {
+            Arguments::All => {
                 config.format.dotfiles = true;
             }
-            &Arguments::List => {
+            Arguments::List => {
                 config.format.inline = false;
                 show_permissions = true;
             }
-            &Arguments::Icon => {
+            Arguments::Icon => {
                 config.format.icons = true;
+
+                if let Some(value) = value {
+                    config.format.icon_set = match value.parse() {
+                        Ok(icon_set) => Some(icon_set),
+                        Err(e) => {
+                            eprintln!("lse: {}", e);
+                            exit(2);
+                        }
+                    }
+                }
             }
-            &Arguments::Help => {
-                return generate_help_text();
+            Arguments::Help => {
+                let _ = generate_help_text();
+                return EXIT_OK;
             }
-            &Arguments::Recursive => recursive = true,
-        };
-    }
-
-    if config.format.inline {
-        inline_format(&config, path)
-    } else {
-        if recursive {
-            recursive_format_ls(&config, 0, &path, show_permissions)
-        } else {
-            multi_line_format(&config, path, show_permissions)
-        }
-    }
-}
-
-pub fn inline_format(config: &Config, path: String) -> std::io::Result<()> {
-    let mut directories: Vec<String> = Vec::new();
-    let mut files: Vec<String> = Vec::new();
-    let mut symlinks: Vec<String> = Vec::new();
-    let mut result: Vec<String> = Vec::new();
-
-    let mut max_str_size: usize = 0;
-
-    for entry in fs::read_dir(path)? {
-        let entry = entry?;
-        let metadata = entry.metadata()?;
-        let file_name = entry.file_name();
-        let file_name_str = file_name.to_string_lossy().to_string();
-
-        if file_name_str.len() > max_str_size {
-            max_str_size = file_name_str.len();
-        }
-
-        if metadata.is_dir() {
-            if !config.format.dotfiles && file_name_str.starts_with(".") {
-                continue;
-            } else {
-                if file_name_str == ".github" {
-                    if config.format.icons {
-                        directories.push(format!("{} {}", "󰊤".blue(), file_name_str.blue().bold()))
-                    } else {
-                        directories.push(format!("{}", file_name_str.blue().bold()))
+            Arguments::Recursive => recursive = true,
+            Arguments::Columns => {
+                config.format.inline = true;
+                direction = GridDirection::Down;
+            }
+            Arguments::Across => {
+                config.format.inline = true;
+                direction = GridDirection::Across;
+            }
+            Arguments::Color => color_when = parse_when("--color", value),
+            Arguments::Hyperlink => hyperlink_when = parse_when("--hyperlink", value),
+            Arguments::QuotingStyle => {
+                config.format.quoting_style = match value.unwrap_or_default().parse() {
+                    Ok(style) => Some(style),
+                    Err(e) => {
+                        eprintln!("lse: {}", e);
+                        exit(2);
                     }
-                } else {
-                    if config.format.icons {
-                        directories.push(format!("{} {}", "".blue(), file_name_str.blue().bold()))
-                    } else {
-                        directories.push(format!("{}", file_name_str.blue().bold()))
+                }
+            }
+            Arguments::Zero => zero = true,
+            Arguments::Json => json = true,
+            Arguments::Theme => config.format.theme = value,
+            Arguments::ExportLsColors => export = true,
+            Arguments::Sniff => config.format.sniff = true,
+            Arguments::Sort => {
+                sort = match value.unwrap_or_default().parse() {
+                    Ok(sort) => sort,
+                    Err(e) => {
+                        eprintln!("lse: {}", e);
+                        exit(2);
+                    }
+                }
+            }
+            Arguments::Only => {
+                only = match value
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::parse)
+                    .collect()
+                {
+                    Ok(only) => only,
+                    Err(e) => {
+                        eprintln!("lse: {}", e);
+                        exit(2);
                     }
                 }
             }
-        } else if metadata.is_file() {
-            if !config.format.dotfiles && file_name_str.starts_with(".") {
-                continue;
-            } else {
-                files.push(format!(
-                    "{}",
-                    files::render_file(
-                        file_name_str.to_string(),
-                        files::get_file_type(file_name_str.to_string()),
-                        &config
-                    )
-                ));
+            Arguments::Mime => {
+                config.format.inline = false;
+                show_mime = true;
+            }
+            Arguments::Image => {
+                config.format.inline = false;
+                show_image = true;
             }
-        } else if metadata.is_symlink() {
-            if !config.format.dotfiles && file_name_str.starts_with(".") {
-                continue;
-            } else {
-                symlinks.push(format!("{}", file_name_str.green().bold()));
+            Arguments::Media => {
+                config.format.inline = false;
+                show_media = true;
+            }
+            Arguments::Classify => config.format.indicator_style = IndicatorStyle::Classify,
+            Arguments::IndicatorStyle => {
+                config.format.indicator_style = match value.unwrap_or_default().parse() {
+                    Ok(style) => style,
+                    Err(e) => {
+                        eprintln!("lse: {}", e);
+                        exit(2);
+                    }
+                }
             }
         }
     }
 
-    for directory in &directories {
-        result.push(directory.to_string());
-    }
+    config.format.icons = config.format.icon_set() != IconSet::None;
 
-    for file in &files {
-        result.push(file.to_string());
-    }
-
-    for symlink in &symlinks {
-        result.push(symlink.to_string());
+    let view = if zero {
+        View::Zero
+    } else if json {
+        View::Json
+    } else if config.format.inline {
+        View::Grid(direction)
+    } else if recursive {
+        View::Tree
+    } else {
+        View::Long
+    };
+
+    if !zero && !json {
+        config.format.colors = apply_color_choice(color_when);
+        config.format.hyperlink = when_stdout_is_terminal(hyperlink_when);
+        config.format.quoting_style = config
+            .format
+            .quoting_style
+            .or(Some(QuotingStyle::default_for_stdout()));
     }
 
-    let (term_width, _) = term_size::dimensions().unwrap_or((80, 25));
-    let column_width = max_str_size + 2;
-    let num_columns = cmp::max(1, term_width / column_width);
-
-    for (i, directory) in result.iter().enumerate() {
-        print!("{:<width$}  ", directory, width = column_width);
-        if (i + 1) % num_columns == 0 {
-            println!();
+    if let Some(background) = &config.format.background {
+        if let Err(e) = ColorValue::parse(background) {
+            eprintln!("lse: invalid background: {}", e);
+            exit(2);
         }
     }
 
-    if directories.len() % num_columns != 0 {
-        println!();
-    }
-
-    Ok(())
-}
-
-pub fn multi_line_format(
-    config: &Config,
-    path: String,
-    show_permissions: bool,
-) -> std::io::Result<()> {
-    let mut result: Vec<String> = Vec::new();
-    for entry in fs::read_dir(path.clone())? {
-        let entry = entry?;
-        let metadata = entry.metadata()?;
-        let file_name = entry.file_name();
-        let file_name_str = file_name.to_string_lossy();
-        let permissions = {
-            if show_permissions {
-                format!(
-                    "{} ",
-                    format_permissions(
-                        config,
-                        &format!("{}/{}", path, &file_name_str),
-                        read_permission(&format!("{}/{}", path, &file_name_str))
-                    )
-                )
-            } else {
-                "".to_string()
-            }
-        };
-        if metadata.is_dir() {
-            if !config.format.dotfiles && file_name_str.starts_with(".") {
-                continue;
-            } else {
-                if file_name_str == ".github" {
-                    if config.format.icons {
-                        result.push(format!(
-                            "{}{} {}",
-                            permissions,
-                            "󰊤".blue(),
-                            file_name_str.blue().bold()
-                        ))
-                    } else {
-                        result.push(format!("{}{}", permissions, file_name_str.blue().bold()))
-                    }
-                } else {
-                    if config.format.icons {
-                        result.push(format!(
-                            "{}{} {}",
-                            permissions,
-                            "".blue(),
-                            file_name_str.blue().bold()
-                        ))
-                    } else {
-                        result.push(format!("{}{}", permissions, file_name_str.blue().bold()))
-                    }
-                }
-            }
-        } else if metadata.is_file() {
-            if !config.format.dotfiles && file_name_str.starts_with(".") {
-                continue;
-            } else {
-                result.push(format!(
-                    "{}{}",
-                    permissions,
-                    files::render_file(
-                        file_name_str.to_string(),
-                        files::get_file_type(file_name_str.to_string()),
-                        &config
-                    )
-                ));
-            }
-        } else if metadata.is_symlink() {
-            if !config.format.dotfiles && file_name_str.starts_with(".") {
-                continue;
-            } else {
-                result.push(format!("{}{}", permissions, file_name_str.green().bold()));
-            }
+    let theme_file = match load_theme_file(config.format.theme.as_deref().unwrap_or("default")) {
+        Ok(theme_file) => theme_file,
+        Err(e) => {
+            eprintln!("lse: {}", e);
+            exit(2);
         }
-    }
+    };
+    let ls_colors = match config.format.ls_colors {
+        LsColorsMode::Ignore => LsColors::default(),
+        _ => match LsColors::load(config.format.dircolors.as_deref()) {
+            Ok(ls_colors) => ls_colors,
+            Err(e) => {
+                eprintln!(
+                    "lse: cannot read dircolors database '{}': {}",
+                    config.format.dircolors.unwrap_or_default(),
+                    describe(&e)
+                );
+                exit(2);
+            }
+        },
+    };
+    let theme = Theme::with_ls_colors(&config, &theme_file, ls_colors, config.format.ls_colors);
 
-    for entry in &result {
-        println!("{}", entry);
+    if export {
+        println!("{}", export_ls_colors(&theme, color_support()));
+        return EXIT_OK;
     }
 
-    Ok(())
+    let lister = paths
+        .into_iter()
+        .fold(Lister::new(config), |lister, path| lister.add_path(path))
+        .set_theme(theme)
+        .set_view(view)
+        .set_show_permissions(show_permissions)
+        .set_show_mime(show_mime)
+        .set_show_image(show_image)
+        .set_show_media(show_media)
+        .set_recursive(recursive)
+        .set_sort(sort)
+        .set_only(only);
+
+    let mut errors = Errors::new();
+    let _ = lister.write_to(&mut io::stdout().lock(), &mut errors);
+
+    errors.exit_code()
 }
 
-pub fn recursive_format_ls(
-    config: &Config,
-    depth: i8,
-    path: &str,
-    show_permissions: bool,
-) -> std::io::Result<()> {
-    println!("{}\n", path.bold().black().on_purple());
-    let _ = multi_line_format(config, path.to_string(), show_permissions);
-    println!();
-
-    for entry in fs::read_dir(path)? {
-        let entry = entry?;
-        let metadata = entry.metadata()?;
-        let file_name = entry.file_name();
-        let file_name_str = file_name.to_string_lossy();
-        if metadata.is_dir() {
-            recursive_format_ls(
-                config,
-                depth + 1,
-                &format!("{}/{}", path, file_name_str),
-                show_permissions,
-            )?
+fn parse_when(flag: &str, value: Option<String>) -> ColorWhen {
+    match value.unwrap_or_default().parse() {
+        Ok(when) => when,
+        Err(e) => {
+            eprintln!(
+                "lse: {} for '{}', valid arguments are 'auto', 'always' and 'never'",
+                e, flag
+            );
+            exit(2);
         }
     }
-
-    Ok(())
 }
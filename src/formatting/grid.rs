@@ -0,0 +1,212 @@
+use std::env;
+use std::io::{self, Write};
+use unicode_width::UnicodeWidthChar;
+
+use crate::{
+    config::Config,
+    formatting::render::{render_entry, Renderer},
+    theme::Theme,
+    types::entry::{Entry, EntryKind},
+};
+
+/// Space between two columns of the grid
+const COLUMN_SPACING: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridDirection {
+    /// Fill the first column from top to bottom, then the next one (`-C`)
+    Down,
+    /// Fill the first row from left to right, then the next one (`-x`)
+    Across,
+}
+
+pub struct Cell {
+    pub contents: String,
+    pub width: usize,
+}
+
+impl Cell {
+    pub fn new(contents: impl Into<String>) -> Cell {
+        let contents = contents.into();
+        let width = display_width(&contents);
+        Cell { contents, width }
+    }
+}
+
+/// Computes how many terminal columns `string` takes once printed.
+///
+/// Escape sequences (colors as well as OSC sequences like hyperlinks) take no room, and wide
+/// characters (CJK, emoji, ...) take two columns.
+pub fn display_width(string: &str) -> usize {
+    let mut width: usize = 0;
+    let mut chars = string.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            width += c.width().unwrap_or(0);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI: ESC [ parameters final-byte
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: ESC ] ... terminated by BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    width
+}
+
+/// Width of the terminal, falling back to `$COLUMNS` and then to 80 columns
+pub fn terminal_width() -> usize {
+    if let Some((width, _)) = term_size::dimensions() {
+        return width;
+    }
+
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .filter(|columns: &usize| *columns > 0)
+        .unwrap_or(80)
+}
+
+struct Layout {
+    rows: usize,
+    columns: usize,
+    column_widths: Vec<usize>,
+}
+
+fn column_of(index: usize, rows: usize, columns: usize, direction: GridDirection) -> usize {
+    match direction {
+        GridDirection::Down => index / rows,
+        GridDirection::Across => index % columns,
+    }
+}
+
+fn try_layout(cells: &[Cell], columns: usize, direction: GridDirection) -> Layout {
+    let rows = cells.len().div_ceil(columns);
+    // when filling downwards, fewer columns may be needed than asked for
+    let columns = match direction {
+        GridDirection::Down => cells.len().div_ceil(rows),
+        GridDirection::Across => columns,
+    };
+    let mut column_widths = vec![0; columns];
+
+    for (i, cell) in cells.iter().enumerate() {
+        let column = column_of(i, rows, columns, direction);
+        column_widths[column] = column_widths[column].max(cell.width);
+    }
+
+    Layout {
+        rows,
+        columns,
+        column_widths,
+    }
+}
+
+/// Packs the cells into as many columns as the width allows, like GNU ls does: every column is
+/// only as wide as its widest cell.
+fn fit_into_width(cells: &[Cell], width: usize, direction: GridDirection) -> Layout {
+    let narrowest = cells.iter().map(|cell| cell.width).min().unwrap_or(0) + COLUMN_SPACING;
+    let max_columns = (width / narrowest.max(1)).clamp(1, cells.len());
+
+    for columns in (2..=max_columns).rev() {
+        let layout = try_layout(cells, columns, direction);
+        let total: usize =
+            layout.column_widths.iter().sum::<usize>() + COLUMN_SPACING * (layout.columns - 1);
+
+        if total <= width {
+            return layout;
+        }
+    }
+
+    try_layout(cells, 1, direction)
+}
+
+/// Renders the cells as a grid fitting in `width` terminal columns, one line per row.
+pub fn render_grid(cells: &[Cell], width: usize, direction: GridDirection) -> String {
+    let mut output = String::new();
+
+    if cells.is_empty() {
+        return output;
+    }
+
+    let layout = fit_into_width(cells, width, direction);
+
+    for row in 0..layout.rows {
+        let mut line = String::new();
+        let mut pending_padding: usize = 0;
+
+        for column in 0..layout.columns {
+            let index = match direction {
+                GridDirection::Down => column * layout.rows + row,
+                GridDirection::Across => row * layout.columns + column,
+            };
+
+            let Some(cell) = cells.get(index) else {
+                break;
+            };
+
+            line.push_str(&" ".repeat(pending_padding));
+            line.push_str(&cell.contents);
+            pending_padding = layout.column_widths[column] - cell.width + COLUMN_SPACING;
+        }
+
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Names laid out in columns, directories first, then files, then fifos, sockets and devices,
+/// then symlinks (the default view)
+pub struct GridRenderer<'a> {
+    pub config: &'a Config,
+    pub theme: &'a Theme,
+    pub direction: GridDirection,
+}
+
+impl Renderer for GridRenderer<'_> {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+        let of_kind = |kind: EntryKind| entries.iter().filter(move |entry| entry.kind == kind);
+        let special = entries.iter().filter(|entry| {
+            !matches!(
+                entry.kind,
+                EntryKind::Directory | EntryKind::File | EntryKind::Symlink
+            )
+        });
+
+        let cells: Vec<Cell> = of_kind(EntryKind::Directory)
+            .chain(of_kind(EntryKind::File))
+            .chain(special)
+            .chain(of_kind(EntryKind::Symlink))
+            .map(|entry| render_entry(self.config, self.theme, entry))
+            .map(Cell::new)
+            .collect();
+
+        write!(
+            out,
+            "{}",
+            render_grid(&cells, terminal_width(), self.direction)
+        )
+    }
+}
@@ -0,0 +1,58 @@
+use std::env;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+fn hostname() -> &'static str {
+    static HOSTNAME: OnceLock<String> = OnceLock::new();
+
+    HOSTNAME.get_or_init(|| {
+        fs::read_to_string("/proc/sys/kernel/hostname")
+            .or_else(|_| fs::read_to_string("/etc/hostname"))
+            .map(|hostname| hostname.trim().to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// The working directory relative paths are joined to, read once per run
+fn current_dir() -> Option<&'static Path> {
+    static CURRENT_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+    CURRENT_DIR
+        .get_or_init(|| env::current_dir().ok())
+        .as_deref()
+}
+
+/// Percent-encodes every byte of the path except the unreserved characters and `/`
+fn percent_encode(path: &Path) -> String {
+    let mut encoded = String::new();
+
+    for &byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+/// Builds the `file://host/absolute/path` URL of `path`, without resolving symlinks
+pub fn file_url(path: &Path) -> String {
+    let absolute: PathBuf = current_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or(path.to_path_buf())
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .collect();
+
+    format!("file://{}{}", hostname(), percent_encode(&absolute))
+}
+
+/// Wraps `text` in an OSC 8 escape sequence pointing to `path`
+pub fn hyperlink(text: &str, path: &Path) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", file_url(path), text)
+}
@@ -0,0 +1,57 @@
+use serde_derive::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::types::entry::{Entry, EntryKind};
+
+/// The same indicator styles as GNU ls' `--indicator-style`
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IndicatorStyle {
+    /// No suffix at all
+    #[default]
+    None,
+    /// `/` after directories
+    Slash,
+    /// `/` after directories, `@` after symlinks, `|` after fifos and `=` after sockets
+    FileType,
+    /// Like `file-type`, plus `*` after executables (`-F`)
+    Classify,
+}
+
+impl FromStr for IndicatorStyle {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "none" => Ok(IndicatorStyle::None),
+            "slash" => Ok(IndicatorStyle::Slash),
+            "file-type" => Ok(IndicatorStyle::FileType),
+            "classify" => Ok(IndicatorStyle::Classify),
+            _ => Err(format!(
+                "invalid argument '{}' for '--indicator-style', valid arguments are 'none', 'slash', 'file-type' and 'classify'",
+                value
+            )),
+        }
+    }
+}
+
+impl IndicatorStyle {
+    /// The suffix printed after the name of `entry`, empty when there is none
+    pub fn indicator(&self, entry: &Entry) -> &'static str {
+        let suffix = match entry.kind {
+            EntryKind::Directory => "/",
+            EntryKind::Symlink => "@",
+            EntryKind::Fifo => "|",
+            EntryKind::Socket => "=",
+            EntryKind::File if entry.is_executable() => "*",
+            _ => "",
+        };
+
+        match self {
+            IndicatorStyle::None => "",
+            IndicatorStyle::Slash if entry.kind != EntryKind::Directory => "",
+            IndicatorStyle::FileType if suffix == "*" => "",
+            _ => suffix,
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    config::Config,
+    formatting::{
+        permissions::format_permissions,
+        render::{render_entry, Renderer},
+    },
+    theme::Theme,
+    types::entry::Entry,
+};
+
+/// One entry per line, optionally preceded by its permissions, size and modification time
+/// (`-l`), MIME type (`--mime`),
+/// image metadata (`--image`) and audio or video metadata (`--media`)
+pub struct LongRenderer<'a> {
+    pub config: &'a Config,
+    pub theme: &'a Theme,
+    pub show_permissions: bool,
+    pub show_mime: bool,
+    pub show_image: bool,
+    pub show_media: bool,
+}
+
+/// `YYYY-MM-DD HH:MM` in UTC, like `--time-style=long-iso` of GNU ls
+fn format_time(time: SystemTime) -> String {
+    let seconds = match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    };
+    let (days, seconds) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+
+    // the date of the proleptic Gregorian calendar `days` after 1970-01-01
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds / 60 % 60
+    )
+}
+
+/// The size in bytes and the modification time of an entry, empty when unknown
+fn stat_cells(entry: &Entry) -> [String; 2] {
+    [
+        entry.stat.size.to_string(),
+        entry.stat.modified.map(format_time).unwrap_or_default(),
+    ]
+}
+
+/// The dimensions, capture date and camera of an image, empty when unknown
+fn image_cells(entry: &Entry) -> [String; 3] {
+    let Some(image) = &entry.image else {
+        return Default::default();
+    };
+
+    [
+        image
+            .dimensions
+            .map(|(width, height)| format!("{}x{}", width, height))
+            .unwrap_or_default(),
+        image.captured.clone().unwrap_or_default(),
+        image.camera.clone().unwrap_or_default(),
+    ]
+}
+
+/// The duration, bitrate or resolution, and container and codec of an audio or video file,
+/// empty when unknown
+fn media_cells(entry: &Entry) -> [String; 3] {
+    let Some(media) = &entry.media else {
+        return Default::default();
+    };
+
+    let detail = match (media.resolution, media.bitrate) {
+        (Some((width, height)), _) => format!("{}x{}", width, height),
+        (None, Some(bitrate)) => format!("{} kb/s", (bitrate + 500) / 1000),
+        (None, None) => String::new(),
+    };
+    let format = match &media.codec {
+        Some(codec) if codec != media.container => format!("{}/{}", media.container, codec),
+        _ => media.container.to_string(),
+    };
+
+    [
+        media.duration.map(format_duration).unwrap_or_default(),
+        detail,
+        format,
+    ]
+}
+
+/// `m:ss`, or `h:mm:ss` from an hour on
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// The width of each column of `cells`, 0 for the columns nothing has a value for
+fn column_widths<const N: usize>(cells: &[[String; N]]) -> [usize; N] {
+    std::array::from_fn(|column| {
+        cells
+            .iter()
+            .map(|cells| cells[column].width())
+            .max()
+            .unwrap_or_default()
+    })
+}
+
+/// Writes the cells padded to `widths`, leaving out the empty columns
+fn write_cells(out: &mut dyn Write, cells: &[String], widths: &[usize]) -> io::Result<()> {
+    for (cell, width) in cells.iter().zip(widths) {
+        if *width > 0 {
+            write!(out, "{}{} ", cell, " ".repeat(width - cell.width()))?;
+        }
+    }
+
+    Ok(())
+}
+
+impl Renderer for LongRenderer<'_> {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+        let mime_width = if self.show_mime {
+            entries
+                .iter()
+                .map(|entry| entry.mime().len())
+                .max()
+                .unwrap_or_default()
+        } else {
+            0
+        };
+        let stat_cells: Vec<[String; 2]> = entries.iter().map(stat_cells).collect();
+        let stat_widths = column_widths(&stat_cells);
+        let image_cells: Vec<[String; 3]> = entries.iter().map(image_cells).collect();
+        let image_widths = column_widths(&image_cells);
+        let media_cells: Vec<[String; 3]> = entries.iter().map(media_cells).collect();
+        let media_widths = column_widths(&media_cells);
+
+        for (i, entry) in entries.iter().enumerate() {
+            if self.show_permissions {
+                write!(out, "{} ", format_permissions(self.theme, entry))?;
+                // sizes are aligned to the right, like in `ls -l`
+                let [size, modified] = &stat_cells[i];
+                write!(out, "{:>width$} ", size, width = stat_widths[0])?;
+                write_cells(out, std::slice::from_ref(modified), &stat_widths[1..])?;
+            }
+
+            if self.show_mime {
+                write!(out, "{:<width$} ", entry.mime(), width = mime_width)?;
+            }
+
+            if self.show_image {
+                write_cells(out, &image_cells[i], &image_widths)?;
+            }
+
+            if self.show_media {
+                write_cells(out, &media_cells[i], &media_widths)?;
+            }
+
+            writeln!(out, "{}", render_entry(self.config, self.theme, entry))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use serde_derive::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::time::UNIX_EPOCH;
+
+use crate::{
+    formatting::render::Renderer,
+    types::{
+        entry::{Entry, EntryKind},
+        image::ImageInfo,
+        media::MediaInfo,
+    },
+};
+
+/// Raw names separated by NUL characters (`--zero`)
+pub struct ZeroRenderer;
+
+impl Renderer for ZeroRenderer {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+        for entry in entries {
+            out.write_all(entry.name.as_bytes())?;
+            out.write_all(b"\0")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    name: String,
+    /// Only present when the name isn't valid UTF-8: `name` is then lossy, this is exact
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name_bytes: Option<&'a [u8]>,
+    path: String,
+    kind: EntryKind,
+    file_type: &'static str,
+    category: &'static str,
+    mime: &'static str,
+    size: u64,
+    mode: u32,
+    /// Seconds since the Unix epoch
+    modified: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symlink_target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<&'a ImageInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    media: Option<&'a MediaInfo>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    plugin_data: &'a HashMap<String, String>,
+}
+
+impl<'a> JsonEntry<'a> {
+    fn new(entry: &'a Entry) -> JsonEntry<'a> {
+        let name_bytes = entry.name.as_bytes();
+
+        JsonEntry {
+            name: entry.name.to_string_lossy().to_string(),
+            name_bytes: entry.name.to_str().is_none().then_some(name_bytes),
+            path: entry.path.to_string_lossy().to_string(),
+            kind: entry.kind,
+            file_type: entry.file_type.config_key(),
+            category: entry.file_type.category().config_key(),
+            mime: entry.mime(),
+            size: entry.stat.size,
+            mode: entry.stat.mode,
+            modified: entry
+                .stat
+                .modified
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs()),
+            symlink_target: entry
+                .symlink_target
+                .as_ref()
+                .map(|target| target.to_string_lossy().to_string()),
+            image: entry.image.as_ref(),
+            media: entry.media.as_ref(),
+            plugin_data: &entry.plugin_data,
+        }
+    }
+}
+
+/// One JSON object per line and per entry (`--json`), children of recursive listings included
+pub struct JsonRenderer;
+
+impl Renderer for JsonRenderer {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+        for entry in entries {
+            serde_json::to_writer(&mut *out, &JsonEntry::new(entry))?;
+            writeln!(out)?;
+            self.render(&entry.children, out)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,2 +1,12 @@
+pub mod color;
+pub mod errors;
 pub mod format;
+pub mod grid;
+pub mod hyperlink;
+pub mod indicator;
+pub mod long;
+pub mod machine;
 pub mod permissions;
+pub mod quoting;
+pub mod render;
+pub mod tree;
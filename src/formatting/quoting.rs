@@ -0,0 +1,247 @@
+use serde_derive::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::io::{self, IsTerminal};
+use std::os::unix::ffi::OsStrExt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// The same quoting styles as GNU ls' `--quoting-style`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuotingStyle {
+    /// Print the name as is (control characters become `?` on a terminal, invalid bytes always do)
+    Literal,
+    /// Quote the name for a shell when needed, control characters become `?`
+    Shell,
+    /// Quote the name for a shell when needed, control characters use `$'...'`
+    ShellEscape,
+    /// Quote the name like a C string
+    C,
+    /// Escape like a C string, without the surrounding quotes
+    Escape,
+}
+
+impl FromStr for QuotingStyle {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "literal" => Ok(QuotingStyle::Literal),
+            "shell" => Ok(QuotingStyle::Shell),
+            "shell-escape" => Ok(QuotingStyle::ShellEscape),
+            "c" => Ok(QuotingStyle::C),
+            "escape" => Ok(QuotingStyle::Escape),
+            _ => Err(format!(
+                "invalid argument '{}' for '--quoting-style', valid arguments are 'literal', 'shell', 'shell-escape', 'c' and 'escape'",
+                value
+            )),
+        }
+    }
+}
+
+impl QuotingStyle {
+    /// What GNU ls does: escape names on a terminal, output them as they are otherwise
+    pub fn default_for_stdout() -> QuotingStyle {
+        if stdout_is_terminal() {
+            QuotingStyle::ShellEscape
+        } else {
+            QuotingStyle::Literal
+        }
+    }
+}
+
+fn stdout_is_terminal() -> bool {
+    static IS_TERMINAL: OnceLock<bool> = OnceLock::new();
+    *IS_TERMINAL.get_or_init(|| io::stdout().is_terminal())
+}
+
+/// Characters a shell interprets anywhere in a word
+const SHELL_SPECIAL: &str = " \t!\"$&'()*;<>?[\\]`{|}";
+/// Characters a shell interprets at the start of a word
+const SHELL_SPECIAL_FIRST: &str = "~#";
+
+enum Piece<'a> {
+    Text(&'a str),
+    Invalid(&'a [u8]),
+}
+
+/// Splits the raw name into runs of valid UTF-8 and invalid bytes
+fn pieces(name: &OsStr) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+
+    for chunk in name.as_bytes().utf8_chunks() {
+        if !chunk.valid().is_empty() {
+            pieces.push(Piece::Text(chunk.valid()));
+        }
+        if !chunk.invalid().is_empty() {
+            pieces.push(Piece::Invalid(chunk.invalid()));
+        }
+    }
+
+    pieces
+}
+
+fn is_printable(c: char) -> bool {
+    !c.is_control()
+}
+
+/// The name with a `?` for each invalid byte and, if `hide_control`, for each control character,
+/// like GNU ls prints them
+fn with_question_marks(name: &OsStr, hide_control: bool) -> String {
+    let mut text = String::new();
+
+    for piece in pieces(name) {
+        match piece {
+            Piece::Text(run) => text.extend(run.chars().map(|c| {
+                if hide_control && !is_printable(c) {
+                    '?'
+                } else {
+                    c
+                }
+            })),
+            Piece::Invalid(bytes) => text.extend(bytes.iter().map(|_| '?')),
+        }
+    }
+
+    text
+}
+
+fn c_escape(c: char, in_quotes: bool) -> Option<String> {
+    let escaped = match c {
+        '\x07' => "\\a",
+        '\x08' => "\\b",
+        '\x0c' => "\\f",
+        '\n' => "\\n",
+        '\r' => "\\r",
+        '\t' => "\\t",
+        '\x0b' => "\\v",
+        '\\' => "\\\\",
+        '"' if in_quotes => "\\\"",
+        ' ' if !in_quotes => "\\ ",
+        c if !is_printable(c) => {
+            let mut buffer = [0; 4];
+            return Some(octal_escape(c.encode_utf8(&mut buffer).as_bytes()));
+        }
+        _ => return None,
+    };
+
+    Some(escaped.to_string())
+}
+
+fn octal_escape(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("\\{:03o}", byte)).collect()
+}
+
+fn quote_c_like(name: &OsStr, in_quotes: bool) -> String {
+    let mut quoted = String::new();
+
+    for piece in pieces(name) {
+        match piece {
+            Piece::Text(text) => {
+                for c in text.chars() {
+                    match c_escape(c, in_quotes) {
+                        Some(escaped) => quoted.push_str(&escaped),
+                        None => quoted.push(c),
+                    }
+                }
+            }
+            Piece::Invalid(bytes) => quoted.push_str(&octal_escape(bytes)),
+        }
+    }
+
+    if in_quotes {
+        format!("\"{}\"", quoted)
+    } else {
+        quoted
+    }
+}
+
+fn needs_shell_quotes(name: &OsStr) -> bool {
+    let bytes = name.as_bytes();
+
+    bytes.is_empty()
+        || SHELL_SPECIAL_FIRST.as_bytes().contains(&bytes[0])
+        || bytes
+            .iter()
+            .any(|byte| SHELL_SPECIAL.as_bytes().contains(byte) || byte.is_ascii_control())
+        || pieces(name)
+            .iter()
+            .any(|piece| matches!(piece, Piece::Invalid(_)))
+        || name.to_string_lossy().chars().any(|c| !is_printable(c))
+}
+
+/// Wraps a run of printable text in single quotes, or in double quotes when it only
+/// contains single quotes as special characters (like GNU ls prints `"it's"`)
+fn shell_quote_text(text: &str) -> String {
+    if text.contains('\'') && !text.contains(['"', '$', '`', '\\', '!']) {
+        format!("\"{}\"", text)
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+fn quote_shell(name: &OsStr, escape: bool) -> String {
+    if !needs_shell_quotes(name) {
+        return name.to_string_lossy().to_string();
+    }
+
+    if !escape {
+        return shell_quote_text(&with_question_marks(name, true));
+    }
+
+    // printable runs go in regular quotes, everything else in $'...' runs
+    let mut quoted = String::new();
+    let mut text = String::new();
+    let mut escaped = String::new();
+
+    let flush_text = |text: &mut String, quoted: &mut String| {
+        if !text.is_empty() {
+            quoted.push_str(&shell_quote_text(text));
+            text.clear();
+        }
+    };
+    let flush_escaped = |escaped: &mut String, quoted: &mut String| {
+        if !escaped.is_empty() {
+            quoted.push_str(&format!("$'{}'", escaped));
+            escaped.clear();
+        }
+    };
+
+    for piece in pieces(name) {
+        match piece {
+            Piece::Text(run) => {
+                for c in run.chars() {
+                    if is_printable(c) {
+                        flush_escaped(&mut escaped, &mut quoted);
+                        text.push(c);
+                    } else {
+                        flush_text(&mut text, &mut quoted);
+                        escaped.push_str(&c_escape(c, true).unwrap_or_default());
+                    }
+                }
+            }
+            Piece::Invalid(bytes) => {
+                flush_text(&mut text, &mut quoted);
+                escaped.push_str(&octal_escape(bytes));
+            }
+        }
+    }
+
+    flush_text(&mut text, &mut quoted);
+    flush_escaped(&mut escaped, &mut quoted);
+
+    quoted
+}
+
+/// Turns a raw file name into something safe to print with the given style.
+///
+/// Only `literal` may output control characters, and only when stdout isn't a terminal.
+pub fn quote_name(name: &OsStr, style: QuotingStyle) -> String {
+    match style {
+        QuotingStyle::Literal => with_question_marks(name, stdout_is_terminal()),
+        QuotingStyle::Shell => quote_shell(name, false),
+        QuotingStyle::ShellEscape => quote_shell(name, true),
+        QuotingStyle::C => quote_c_like(name, true),
+        QuotingStyle::Escape => quote_c_like(name, false),
+    }
+}
@@ -0,0 +1,130 @@
+use serde_derive::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+use crate::{
+    config::{Color, Config},
+    formatting::{
+        hyperlink::hyperlink,
+        quoting::{quote_name, QuotingStyle},
+    },
+    theme::{Element, Theme},
+    types::{
+        entry::{Entry, EntryKind},
+        files::{is_backup, is_build_artifact, FileType},
+    },
+};
+
+/// Turns the entries of one directory into text
+pub trait Renderer {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()>;
+}
+
+/// The file name as it should be printed: quoted, and wrapped in a hyperlink if asked to (except
+/// inside archives, where there is nothing to link to)
+pub fn display_name(config: &Config, entry: &Entry) -> String {
+    let quoted = quote_name(
+        &entry.name,
+        config.format.quoting_style.unwrap_or(QuotingStyle::Literal),
+    );
+
+    if config.format.hyperlink && !entry.archived {
+        hyperlink(&quoted, &entry.path)
+    } else {
+        quoted
+    }
+}
+
+/// What the names of regular files are colored by
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorNames {
+    /// The color of their file type, like their icon
+    #[default]
+    Type,
+    /// Whether they are executables, archives, media, build artifacts or backups
+    Kind,
+    /// Names stay plain
+    None,
+}
+
+/// The element fifos, sockets and devices are drawn with
+fn kind_element(entry: &Entry) -> Option<Element> {
+    match entry.kind {
+        EntryKind::Symlink => Some(Element::Symlink),
+        EntryKind::Fifo => Some(Element::Fifo),
+        EntryKind::Socket => Some(Element::Socket),
+        EntryKind::BlockDevice => Some(Element::BlockDevice),
+        EntryKind::CharDevice => Some(Element::CharDevice),
+        _ => None,
+    }
+}
+
+/// The element of the kind of a regular file, for `color_names = "kind"`
+fn name_element(entry: &Entry) -> Option<Element> {
+    let name = entry.name.to_string_lossy();
+
+    if entry.is_executable() {
+        Some(Element::Executable)
+    } else if entry.file_type == FileType::Archive {
+        Some(Element::Archive)
+    } else if matches!(
+        entry.file_type,
+        FileType::Photo | FileType::Audio | FileType::Video
+    ) {
+        Some(Element::Media)
+    } else if is_build_artifact(&name) {
+        Some(Element::Artifact)
+    } else if is_backup(&name) {
+        Some(Element::Backup)
+    } else {
+        None
+    }
+}
+
+/// The color of the name of a regular file, according to `color_names`
+fn name_color(config: &Config, theme: &Theme, entry: &Entry) -> Option<Color> {
+    match config.format.color_names {
+        ColorNames::Type => theme.file_color(entry).or_else(|| {
+            entry
+                .is_executable()
+                .then(|| theme.element(Element::Executable))
+        }),
+        ColorNames::Kind => name_element(entry).map(|element| theme.element(element)),
+        ColorNames::None => None,
+    }
+}
+
+/// Icon, name and indicator of an entry, as shown by every human-readable view
+pub fn render_entry(config: &Config, theme: &Theme, entry: &Entry) -> String {
+    let display_name = display_name(config, entry);
+    let indicator = config.format.indicator_style.indicator(entry);
+    let (icon, name) = match (entry.kind, kind_element(entry)) {
+        (EntryKind::Directory, _) => {
+            let (icon, color) = theme.directory_style(&entry.name);
+            (Some(color.paint(icon)), color.paint(&display_name))
+        }
+        (EntryKind::File, _) => {
+            // executables keep the icon of their file type, when they have one
+            let icon = if entry.file_type == FileType::None && entry.is_executable() {
+                theme.paint(Element::Executable, theme.kind_icon(Element::Executable))
+            } else {
+                theme.file_icon(entry).into_owned()
+            };
+            let name = match name_color(config, theme, entry) {
+                Some(color) => color.paint(&display_name),
+                None => display_name,
+            };
+            (Some(icon), name)
+        }
+        (_, Some(element)) => (
+            Some(theme.paint(element, theme.kind_icon(element))),
+            theme.paint(element, &display_name),
+        ),
+        (_, None) => (Some(theme.file_icon(entry).into_owned()), display_name),
+    };
+
+    match icon {
+        Some(icon) if config.format.icons => format!("{} {}{}", icon, name, indicator),
+        _ => format!("{}{}", name, indicator),
+    }
+}
@@ -0,0 +1,30 @@
+use std::io::{self, Write};
+
+use crate::{
+    formatting::{long::LongRenderer, render::Renderer},
+    theme::Element,
+    types::entry::Entry,
+};
+
+/// Every directory under a header, followed by the same for each of its subdirectories (`-r`).
+///
+/// Expects the entries to be collected recursively: it renders the children of the
+/// directories it is given.
+pub struct TreeRenderer<'a> {
+    pub long: LongRenderer<'a>,
+}
+
+impl Renderer for TreeRenderer<'_> {
+    fn render(&self, entries: &[Entry], out: &mut dyn Write) -> io::Result<()> {
+        for directory in entries.iter().filter(|entry| entry.is_container()) {
+            let header = directory.path.display().to_string();
+            writeln!(out, "{}\n", self.long.theme.paint(Element::Header, &header))?;
+            self.long.render(&directory.children, out)?;
+            writeln!(out)?;
+
+            self.render(&directory.children, out)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+//! ls-enhanced as a library: list directories with the same file type detection, icons and
+//! colors as the `ls-enhanced` binary.
+//!
+//! A [`Lister`] collects the [`Entry`]s of each directory, then hands them to a [`Renderer`].
+
+pub mod arguments;
+pub mod config;
+pub mod formatting;
+pub mod lister;
+pub mod ls_colors;
+pub mod palette;
+pub mod theme;
+pub mod types;
+
+pub use crate::{
+    config::Config,
+    formatting::{errors::Errors, render::Renderer},
+    lister::{Lister, View},
+    theme::Theme,
+    types::{entry::Entry, files::FileType},
+};
@@ -0,0 +1,439 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::{
+    config::Config,
+    formatting::{
+        errors::Errors,
+        grid::{GridDirection, GridRenderer},
+        long::LongRenderer,
+        machine::{JsonRenderer, ZeroRenderer},
+        render::Renderer,
+        tree::TreeRenderer,
+    },
+    theme::Theme,
+    types::{
+        archive::{read_archive, ArchiveFormat},
+        entry::{Entry, EntryKind},
+        files::{FileType, TypeSelector},
+        image::read_image_info,
+        media::read_media_info,
+        rules::FileTypeRules,
+    },
+};
+
+/// How the entries get laid out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum View {
+    /// Names in columns
+    Grid(GridDirection),
+    /// One entry per line
+    Long,
+    /// One entry per line, then the same for every subdirectory
+    Tree,
+    /// Raw names separated by NUL characters
+    Zero,
+    /// One JSON object per entry
+    Json,
+}
+
+/// The order of the entries of each directory
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SortKey {
+    /// The order the directory gives them in
+    #[default]
+    None,
+    Name,
+    /// By file type, then by name
+    Type,
+    /// By category, then by file type, then by name
+    Category,
+    /// Photos by EXIF capture date, oldest first, then everything else by name
+    Captured,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "none" => Ok(SortKey::None),
+            "name" => Ok(SortKey::Name),
+            "type" => Ok(SortKey::Type),
+            "category" => Ok(SortKey::Category),
+            "captured" => Ok(SortKey::Captured),
+            _ => Err(format!(
+                "invalid argument '{}' for '--sort', valid arguments are 'none', 'name', 'type', 'category' and 'captured'",
+                value
+            )),
+        }
+    }
+}
+
+impl SortKey {
+    pub fn sort(&self, entries: &mut [Entry]) {
+        match self {
+            SortKey::None => {}
+            SortKey::Name => entries.sort_by(|a, b| a.name.cmp(&b.name)),
+            SortKey::Type => entries.sort_by(|a, b| {
+                (a.file_type.config_key(), &a.name).cmp(&(b.file_type.config_key(), &b.name))
+            }),
+            SortKey::Category => entries.sort_by(|a, b| {
+                let key = |entry: &Entry| entry.file_type.category();
+                (key(a), a.file_type.config_key(), &a.name).cmp(&(
+                    key(b),
+                    b.file_type.config_key(),
+                    &b.name,
+                ))
+            }),
+            SortKey::Captured => entries.sort_by(|a, b| {
+                let captured = |entry: &Entry| {
+                    entry
+                        .image
+                        .as_ref()
+                        .and_then(|image| image.captured.clone())
+                };
+                let (a_captured, b_captured) = (captured(a), captured(b));
+                (a_captured.is_none(), a_captured, &a.name).cmp(&(
+                    b_captured.is_none(),
+                    b_captured,
+                    &b.name,
+                ))
+            }),
+        }
+    }
+}
+
+/// Lists directories: collects their entries, then hands them to a renderer.
+///
+/// ```no_run
+/// use ls_enhanced::{config::create_config, Errors, Lister, View};
+///
+/// let mut errors = Errors::new();
+/// Lister::new(create_config())
+///     .add_path("src")
+///     .set_view(View::Long)
+///     .write_to(&mut std::io::stdout(), &mut errors)
+///     .unwrap();
+/// ```
+pub struct Lister {
+    config: Config,
+    theme: Theme,
+    rules: FileTypeRules,
+    paths: Vec<PathBuf>,
+    view: View,
+    show_permissions: bool,
+    show_mime: bool,
+    show_image: bool,
+    show_media: bool,
+    recursive: bool,
+    sort: SortKey,
+    /// Files kept when not empty, directories are always kept
+    only: Vec<TypeSelector>,
+}
+
+impl Lister {
+    pub fn new(config: Config) -> Lister {
+        let view = if config.format.inline {
+            View::Grid(GridDirection::Down)
+        } else {
+            View::Long
+        };
+
+        Lister {
+            theme: Theme::new(&config),
+            rules: FileTypeRules::new(&config.rules).set_sniff(config.format.sniff),
+            config,
+            paths: Vec::new(),
+            view,
+            show_permissions: false,
+            show_mime: false,
+            show_image: false,
+            show_media: false,
+            recursive: false,
+            sort: SortKey::None,
+            only: Vec::new(),
+        }
+    }
+
+    pub fn add_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    /// Replaces the theme built from the config alone
+    pub fn set_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn set_view(mut self, view: View) -> Self {
+        self.view = view;
+        self
+    }
+
+    pub fn set_show_permissions(mut self, show_permissions: bool) -> Self {
+        self.show_permissions = show_permissions;
+        self
+    }
+
+    /// Shows the MIME type of the entries in the long and tree views
+    pub fn set_show_mime(mut self, show_mime: bool) -> Self {
+        self.show_mime = show_mime;
+        self
+    }
+
+    /// Shows the dimensions of images, and the capture date and camera of photos, in the long
+    /// and tree views
+    pub fn set_show_image(mut self, show_image: bool) -> Self {
+        self.show_image = show_image;
+        self
+    }
+
+    /// Shows the duration, bitrate or resolution and codec of audio and video files in the long
+    /// and tree views
+    pub fn set_show_media(mut self, show_media: bool) -> Self {
+        self.show_media = show_media;
+        self
+    }
+
+    /// Also collects the content of subdirectories for `View::Json`, always the case for
+    /// `View::Tree`. The other views never show it, so nothing more is read for them.
+    pub fn set_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    pub fn set_sort(mut self, sort: SortKey) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Only lists the files of these categories or file types
+    pub fn set_only(mut self, only: Vec<TypeSelector>) -> Self {
+        self.only = only;
+        self
+    }
+
+    pub fn is_recursive(&self) -> bool {
+        match self.view {
+            View::Tree => true,
+            View::Json => self.recursive,
+            View::Grid(_) | View::Long | View::Zero => false,
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// The paths to list, the current directory if none were added
+    pub fn paths(&self) -> Vec<PathBuf> {
+        if self.paths.is_empty() {
+            vec![PathBuf::from(".")]
+        } else {
+            self.paths.clone()
+        }
+    }
+
+    fn long_renderer(&self) -> LongRenderer<'_> {
+        LongRenderer {
+            config: &self.config,
+            theme: &self.theme,
+            show_permissions: self.show_permissions,
+            show_mime: self.show_mime,
+            show_image: self.show_image,
+            show_media: self.show_media,
+        }
+    }
+
+    /// The renderer matching the view
+    pub fn renderer(&self) -> Box<dyn Renderer + '_> {
+        let long = self.long_renderer();
+
+        match self.view {
+            View::Grid(direction) => Box::new(GridRenderer {
+                config: &self.config,
+                theme: &self.theme,
+                direction,
+            }),
+            View::Long => Box::new(long),
+            View::Tree => Box::new(TreeRenderer { long }),
+            View::Zero => Box::new(ZeroRenderer),
+            View::Json => Box::new(JsonRenderer),
+        }
+    }
+
+    /// Reads the entries of `path`, leaving out dotfiles unless they are enabled, and the
+    /// entries of its subdirectories as their children when listing recursively.
+    ///
+    /// Entries that can't be read are reported and skipped, the listing goes on without them.
+    /// `top_level` tells whether `path` was given on the command line, where failing to open
+    /// it is serious trouble.
+    pub fn collect(&self, path: &Path, errors: &mut Errors, top_level: bool) -> Vec<Entry> {
+        let mut entries: Vec<Entry> = Vec::new();
+
+        let read_dir = match fs::read_dir(path) {
+            Ok(read_dir) => read_dir,
+            Err(e) => {
+                if top_level {
+                    errors.cannot_open_directory(path, &e);
+                } else {
+                    errors.cannot_open_subdirectory(path, &e);
+                }
+                return entries;
+            }
+        };
+
+        for entry in read_dir {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    errors.cannot_read_directory(path, &e);
+                    continue;
+                }
+            };
+
+            match entry.metadata() {
+                Ok(metadata) => entries.push(Entry::from_dir_entry(&entry, metadata, &self.rules)),
+                Err(e) => errors.cannot_access(&entry.path(), &e),
+            }
+        }
+
+        self.select(&mut entries);
+
+        if self.is_recursive() {
+            for entry in entries.iter_mut().filter(|entry| entry.is_dir()) {
+                entry.children = self.collect(&entry.path, errors, false);
+            }
+        }
+
+        entries
+    }
+
+    /// Leaves out dotfiles unless they are enabled and the files `only` doesn't match, then
+    /// sorts what's left
+    fn select(&self, entries: &mut Vec<Entry>) {
+        entries.retain(|entry| self.config.format.dotfiles || !entry.is_dotfile());
+        if !self.only.is_empty() {
+            entries.retain(|entry| {
+                entry.is_dir()
+                    || self
+                        .only
+                        .iter()
+                        .any(|selector| selector.matches(entry.file_type))
+            });
+        }
+        self.read_headers(entries);
+        self.sort.sort(entries);
+    }
+
+    /// Reads the headers of images when they are shown or sorted by, and the ones of audio
+    /// and video files when they are shown
+    fn read_headers(&self, entries: &mut [Entry]) {
+        let images = self.show_image || self.sort == SortKey::Captured;
+
+        for entry in entries.iter_mut() {
+            match entry.file_type {
+                FileType::Photo | FileType::Svg if images => {
+                    entry.image = read_image_info(&entry.path, entry.file_type);
+                }
+                FileType::Audio | FileType::Video if self.show_media => {
+                    entry.media = read_media_info(&entry.path);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// `select` for the entries read from an archive and, when listing recursively, for their
+    /// children, which are dropped otherwise
+    fn select_archived(&self, mut entries: Vec<Entry>) -> Vec<Entry> {
+        self.select(&mut entries);
+
+        for entry in &mut entries {
+            entry.children = if self.is_recursive() {
+                self.select_archived(std::mem::take(&mut entry.children))
+            } else {
+                Vec::new()
+            };
+        }
+
+        entries
+    }
+
+    /// The entry of a path given to the lister, with its content as children.
+    ///
+    /// Symlinks to directories are followed, like GNU ls does for its operands, and archives
+    /// are listed like directories.
+    pub fn collect_root(&self, path: &Path, errors: &mut Errors) -> io::Result<Entry> {
+        let metadata = fs::metadata(path).or_else(|_| fs::symlink_metadata(path))?;
+        let mut root = Entry::with_rules(path.as_os_str(), path, metadata, &self.rules);
+
+        if root.is_dir() {
+            root.children = self.collect(path, errors, true);
+        } else if let (EntryKind::File, Some(format)) =
+            (root.kind, ArchiveFormat::of(&path.to_string_lossy()))
+        {
+            root.archive = Some(format);
+            match read_archive(path, format, &self.rules) {
+                Ok(entries) => root.children = self.select_archived(entries),
+                Err(e) => errors.cannot_read_archive(path, &e),
+            }
+        } else {
+            self.read_headers(std::slice::from_mut(&mut root));
+        }
+
+        Ok(root)
+    }
+
+    /// Lists every path into `out`, a header precedes each one when there are several
+    pub fn write_to(&self, out: &mut dyn Write, errors: &mut Errors) -> io::Result<()> {
+        let paths = self.paths();
+        let renderer = self.renderer();
+        let several = paths.len() > 1;
+        let mut files: Vec<Entry> = Vec::new();
+        let mut directories: Vec<Entry> = Vec::new();
+
+        // like GNU ls, paths that can't be accessed are reported before anything gets listed,
+        // then files given on the command line are listed together, then each directory
+        for path in &paths {
+            match self.collect_root(path, errors) {
+                Ok(root) if root.is_container() => directories.push(root),
+                Ok(root) => files.push(root),
+                Err(e) => errors.cannot_access_operand(path, &e),
+            }
+        }
+
+        if !files.is_empty() {
+            match self.view {
+                View::Tree => self.long_renderer().render(&files, out)?,
+                _ => renderer.render(&files, out)?,
+            }
+        }
+
+        for (i, directory) in directories.iter().enumerate() {
+            if self.view == View::Tree {
+                renderer.render(std::slice::from_ref(directory), out)?;
+                continue;
+            }
+
+            if several && matches!(self.view, View::Grid(_) | View::Long) {
+                if i > 0 || !files.is_empty() {
+                    writeln!(out)?;
+                }
+                writeln!(out, "{}:", directory.path.display())?;
+            }
+
+            renderer.render(&directory.children, out)?;
+        }
+
+        Ok(())
+    }
+}
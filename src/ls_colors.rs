@@ -0,0 +1,259 @@
+//! Reading `LS_COLORS` and `dircolors` databases, and writing the lse theme back as `LS_COLORS`.
+
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+
+use crate::{
+    config::Color,
+    palette::{ColorSupport, ColorValue},
+    theme::{Element, Theme},
+    types::files::{FileType, COMPOUND_EXTENSION_TYPES, EXTENSION_TYPES, NAME_TYPES},
+};
+
+/// How `LS_COLORS` combines with the colors of the config and theme
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LsColorsMode {
+    /// `LS_COLORS` wins over the config and theme
+    Prefer,
+    /// `LS_COLORS` is only used for what the config and theme have no color for
+    Fallback,
+    /// `LS_COLORS` isn't read
+    #[default]
+    Ignore,
+}
+
+/// The keywords of a `dircolors` database and their `LS_COLORS` key
+const DIRCOLORS_KEYWORDS: [(&str, &str); 25] = [
+    ("NORMAL", "no"),
+    ("NORM", "no"),
+    ("FILE", "fi"),
+    ("RESET", "rs"),
+    ("DIR", "di"),
+    ("LINK", "ln"),
+    ("LNK", "ln"),
+    ("SYMLINK", "ln"),
+    ("ORPHAN", "or"),
+    ("MISSING", "mi"),
+    ("FIFO", "pi"),
+    ("PIPE", "pi"),
+    ("SOCK", "so"),
+    ("BLK", "bd"),
+    ("BLOCK", "bd"),
+    ("CHR", "cd"),
+    ("CHAR", "cd"),
+    ("DOOR", "do"),
+    ("EXEC", "ex"),
+    ("SETUID", "su"),
+    ("SETGID", "sg"),
+    ("STICKY", "st"),
+    ("OTHER_WRITABLE", "ow"),
+    ("STICKY_OTHER_WRITABLE", "tw"),
+    ("MULTIHARDLINK", "mh"),
+];
+
+/// The colors of an `LS_COLORS` value
+#[derive(Debug, Clone, Default)]
+pub struct LsColors {
+    /// Colors by two-letter key, like `di` for directories or `ex` for executables
+    pub kinds: HashMap<String, Color>,
+    /// Colors of the `*suffix` entries, lowercased, longest suffix first
+    pub suffixes: Vec<(String, Color)>,
+}
+
+/// Turns SGR parameters like `01;38;5;208` into a color, unsupported attributes are left out.
+///
+/// Codes that aren't numbers up to 255 are skipped rather than taken as a reset, while an empty
+/// one is a reset like terminals have it.
+pub fn parse_sgr(sgr: &str) -> Color {
+    let mut color = Color::default();
+    let codes: Vec<Option<u8>> = sgr
+        .split(';')
+        .map(|code| match code {
+            "" => Some(0),
+            code => code.parse().ok(),
+        })
+        .collect();
+    let mut codes = codes.into_iter();
+
+    while let Some(code) = codes.next() {
+        let Some(code) = code else {
+            continue;
+        };
+
+        match code {
+            0 => color = Color::default(),
+            1 => color.bold = true,
+            3 => color.italic = true,
+            4 => color.underline = true,
+            30..=37 => color.foreground = Some(ColorValue::Ansi(code - 30)),
+            39 => color.foreground = None,
+            40..=47 => color.background = Some(ColorValue::Ansi(code - 40)),
+            49 => color.background = None,
+            90..=97 => color.foreground = Some(ColorValue::Ansi(code - 90 + 8)),
+            100..=107 => color.background = Some(ColorValue::Ansi(code - 100 + 8)),
+            38 | 48 => {
+                let value = match codes.next().flatten() {
+                    Some(5) => codes.next().flatten().map(ColorValue::Fixed),
+                    Some(2) => match (
+                        codes.next().flatten(),
+                        codes.next().flatten(),
+                        codes.next().flatten(),
+                    ) {
+                        (Some(red), Some(green), Some(blue)) => {
+                            Some(ColorValue::Rgb(red, green, blue))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+
+                if code == 38 {
+                    color.foreground = value;
+                } else {
+                    color.background = value;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    color
+}
+
+impl LsColors {
+    /// Reads an `LS_COLORS` value, like `di=01;34:ln=01;36:*.tar=01;31`
+    pub fn parse(value: &str) -> LsColors {
+        let mut ls_colors = LsColors::default();
+        ls_colors.extend(value);
+        ls_colors
+    }
+
+    /// Adds the entries of an `LS_COLORS` value, replacing the ones with the same key
+    pub fn extend(&mut self, value: &str) {
+        for entry in value.split(':') {
+            if let Some((key, sgr)) = entry.split_once('=') {
+                self.insert(key, sgr);
+            }
+        }
+
+        self.sort_suffixes();
+    }
+
+    /// Reads a `dircolors` database, like the output of `dircolors --print-database`
+    pub fn parse_dircolors(contents: &str) -> LsColors {
+        let mut ls_colors = LsColors::default();
+
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut words = line.split_whitespace();
+
+            let (Some(keyword), Some(sgr)) = (words.next(), words.next()) else {
+                continue;
+            };
+
+            if keyword.starts_with('.') {
+                ls_colors.insert(&format!("*{}", keyword), sgr);
+            } else if keyword.starts_with('*') {
+                ls_colors.insert(keyword, sgr);
+            } else if let Some((_, key)) = DIRCOLORS_KEYWORDS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(keyword))
+            {
+                ls_colors.insert(key, sgr);
+            }
+        }
+
+        ls_colors.sort_suffixes();
+        ls_colors
+    }
+
+    /// The `dircolors` database at `path` if any, then `LS_COLORS` over it
+    pub fn load(dircolors: Option<&str>) -> io::Result<LsColors> {
+        let mut ls_colors = match dircolors {
+            Some(path) => {
+                let path = match (path.strip_prefix("~/"), env::var("HOME")) {
+                    (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+                    _ => path.to_string(),
+                };
+                LsColors::parse_dircolors(&fs::read_to_string(path)?)
+            }
+            None => LsColors::default(),
+        };
+
+        if let Ok(value) = env::var("LS_COLORS") {
+            ls_colors.extend(&value);
+        }
+
+        Ok(ls_colors)
+    }
+
+    fn insert(&mut self, key: &str, sgr: &str) {
+        let color = parse_sgr(sgr);
+
+        match key.strip_prefix('*') {
+            Some(suffix) => {
+                let suffix = suffix.to_lowercase();
+                self.suffixes.retain(|(known, _)| *known != suffix);
+                self.suffixes.push((suffix, color));
+            }
+            None => {
+                self.kinds.insert(key.to_string(), color);
+            }
+        }
+    }
+
+    fn sort_suffixes(&mut self) {
+        self.suffixes
+            .sort_by_key(|(suffix, _)| std::cmp::Reverse(suffix.len()));
+    }
+
+    pub fn kind(&self, key: &str) -> Option<&Color> {
+        self.kinds.get(key)
+    }
+
+    /// The color of the longest suffix `name` ends with, ignoring case
+    pub fn suffix_color(&self, name: &str) -> Option<&Color> {
+        let name = name.to_lowercase();
+
+        self.suffixes
+            .iter()
+            .find(|(suffix, _)| name.ends_with(suffix.as_str()))
+            .map(|(_, color)| color)
+    }
+}
+
+/// The theme as an `LS_COLORS` value, for every kind of entry and every file lse knows
+pub fn export_ls_colors(theme: &Theme, support: ColorSupport) -> String {
+    let mut entries: Vec<String> = Element::ALL
+        .iter()
+        .filter_map(|element| {
+            let key = element.ls_colors_key()?;
+            Some(format!("{}={}", key, theme.element(*element).sgr(support)))
+        })
+        .collect();
+
+    let extensions = COMPOUND_EXTENSION_TYPES.iter().copied().chain(
+        EXTENSION_TYPES
+            .iter()
+            .map(|(extension, file_type, _)| (*extension, *file_type)),
+    );
+    let patterns = NAME_TYPES
+        .iter()
+        .map(|(name, file_type)| (name.to_string(), *file_type))
+        .chain(extensions.map(|(extension, file_type)| (format!(".{}", extension), file_type)));
+
+    for (pattern, file_type) in patterns {
+        if let Some(color) = theme
+            .color(file_type)
+            .filter(|_| file_type != FileType::None)
+        {
+            entries.push(format!("*{}={}", pattern, color.sgr(support)));
+        }
+    }
+
+    entries.join(":")
+}
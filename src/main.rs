@@ -1,13 +1,7 @@
-pub mod arguments;
-pub mod config;
-pub mod formatting;
-pub mod types;
+use ls_enhanced::{arguments, config, config::Config, formatting::format::format_ls};
+use std::{env, fs, process::exit};
 
-use crate::{arguments::Arguments, config::Config, formatting::format::format_ls};
-use std::{env, fs, path::Path, process::exit};
-use toml;
-
-fn main() -> std::io::Result<()> {
+fn main() {
     let config_file: String = format!(
         "{}/.config/lse/config.toml",
         env::var("HOME").expect("HOME environment variable is not set")
@@ -32,30 +26,7 @@ fn main() -> std::io::Result<()> {
         }
     };
 
-    let possible_args = arguments::create_arg();
-    let mut args: Vec<&Arguments> = Vec::new();
-    let mut path: Option<String> = Some(".".to_string());
-
-    for arg in env::args() {
-        let possible_path = Path::new(&arg);
-        if possible_path.exists() && possible_path.is_dir() {
-            path = Some(possible_path.to_string_lossy().to_string());
-        } else {
-            for possible_arg in &possible_args {
-                if let Some(ref short) = possible_arg.short {
-                    if arg == *short && !args.contains(&possible_arg.get_arg_type()) {
-                        args.push(possible_arg.get_arg_type());
-                    }
-                }
-
-                if let Some(ref long) = possible_arg.long {
-                    if arg == *long && !args.contains(&possible_arg.get_arg_type()) {
-                        args.push(possible_arg.get_arg_type());
-                    }
-                }
-            }
-        }
-    }
+    let (paths, args) = arguments::parse_args(env::args());
 
-    format_ls(config, path.unwrap(), args)
+    exit(format_ls(config, paths, args))
 }
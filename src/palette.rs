@@ -0,0 +1,538 @@
+//! Color values as written in the config and themes, and how they get downgraded to what the
+//! terminal can show.
+
+/// How many colors the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// The 16 colors of the terminal palette
+    Ansi16,
+    /// The 256-color palette
+    Ansi256,
+    /// Any 24-bit color
+    TrueColor,
+}
+
+/// A single color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorValue {
+    /// One of the 16 colors of the terminal palette, which the terminal theme decides
+    Ansi(u8),
+    /// An entry of the 256-color palette
+    Fixed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// Names of the 16 palette colors, `Ansi(i)` is `ANSI_NAMES[i]`
+pub const ANSI_NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "bright-black",
+    "bright-red",
+    "bright-green",
+    "bright-yellow",
+    "bright-blue",
+    "bright-magenta",
+    "bright-cyan",
+    "bright-white",
+];
+
+/// What the 16 palette colors look like in xterm, used to find the closest one
+const ANSI_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Levels of each channel in the 6x6x6 cube of the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The CSS named colors, except the ones sharing their name with a palette color
+const CSS_COLORS: [(&str, (u8, u8, u8)); 140] = [
+    ("aliceblue", (240, 248, 255)),
+    ("antiquewhite", (250, 235, 215)),
+    ("aqua", (0, 255, 255)),
+    ("aquamarine", (127, 255, 212)),
+    ("azure", (240, 255, 255)),
+    ("beige", (245, 245, 220)),
+    ("bisque", (255, 228, 196)),
+    ("blanchedalmond", (255, 235, 205)),
+    ("blueviolet", (138, 43, 226)),
+    ("brown", (165, 42, 42)),
+    ("burlywood", (222, 184, 135)),
+    ("cadetblue", (95, 158, 160)),
+    ("chartreuse", (127, 255, 0)),
+    ("chocolate", (210, 105, 30)),
+    ("coral", (255, 127, 80)),
+    ("cornflowerblue", (100, 149, 237)),
+    ("cornsilk", (255, 248, 220)),
+    ("crimson", (220, 20, 60)),
+    ("darkblue", (0, 0, 139)),
+    ("darkcyan", (0, 139, 139)),
+    ("darkgoldenrod", (184, 134, 11)),
+    ("darkgray", (169, 169, 169)),
+    ("darkgreen", (0, 100, 0)),
+    ("darkgrey", (169, 169, 169)),
+    ("darkkhaki", (189, 183, 107)),
+    ("darkmagenta", (139, 0, 139)),
+    ("darkolivegreen", (85, 107, 47)),
+    ("darkorange", (255, 140, 0)),
+    ("darkorchid", (153, 50, 204)),
+    ("darkred", (139, 0, 0)),
+    ("darksalmon", (233, 150, 122)),
+    ("darkseagreen", (143, 188, 143)),
+    ("darkslateblue", (72, 61, 139)),
+    ("darkslategray", (47, 79, 79)),
+    ("darkslategrey", (47, 79, 79)),
+    ("darkturquoise", (0, 206, 209)),
+    ("darkviolet", (148, 0, 211)),
+    ("deeppink", (255, 20, 147)),
+    ("deepskyblue", (0, 191, 255)),
+    ("dimgray", (105, 105, 105)),
+    ("dimgrey", (105, 105, 105)),
+    ("dodgerblue", (30, 144, 255)),
+    ("firebrick", (178, 34, 34)),
+    ("floralwhite", (255, 250, 240)),
+    ("forestgreen", (34, 139, 34)),
+    ("fuchsia", (255, 0, 255)),
+    ("gainsboro", (220, 220, 220)),
+    ("ghostwhite", (248, 248, 255)),
+    ("gold", (255, 215, 0)),
+    ("goldenrod", (218, 165, 32)),
+    ("gray", (128, 128, 128)),
+    ("greenyellow", (173, 255, 47)),
+    ("grey", (128, 128, 128)),
+    ("honeydew", (240, 255, 240)),
+    ("hotpink", (255, 105, 180)),
+    ("indianred", (205, 92, 92)),
+    ("indigo", (75, 0, 130)),
+    ("ivory", (255, 255, 240)),
+    ("khaki", (240, 230, 140)),
+    ("lavender", (230, 230, 250)),
+    ("lavenderblush", (255, 240, 245)),
+    ("lawngreen", (124, 252, 0)),
+    ("lemonchiffon", (255, 250, 205)),
+    ("lightblue", (173, 216, 230)),
+    ("lightcoral", (240, 128, 128)),
+    ("lightcyan", (224, 255, 255)),
+    ("lightgoldenrodyellow", (250, 250, 210)),
+    ("lightgray", (211, 211, 211)),
+    ("lightgreen", (144, 238, 144)),
+    ("lightgrey", (211, 211, 211)),
+    ("lightpink", (255, 182, 193)),
+    ("lightsalmon", (255, 160, 122)),
+    ("lightseagreen", (32, 178, 170)),
+    ("lightskyblue", (135, 206, 250)),
+    ("lightslategray", (119, 136, 153)),
+    ("lightslategrey", (119, 136, 153)),
+    ("lightsteelblue", (176, 196, 222)),
+    ("lightyellow", (255, 255, 224)),
+    ("lime", (0, 255, 0)),
+    ("limegreen", (50, 205, 50)),
+    ("linen", (250, 240, 230)),
+    ("maroon", (128, 0, 0)),
+    ("mediumaquamarine", (102, 205, 170)),
+    ("mediumblue", (0, 0, 205)),
+    ("mediumorchid", (186, 85, 211)),
+    ("mediumpurple", (147, 112, 219)),
+    ("mediumseagreen", (60, 179, 113)),
+    ("mediumslateblue", (123, 104, 238)),
+    ("mediumspringgreen", (0, 250, 154)),
+    ("mediumturquoise", (72, 209, 204)),
+    ("mediumvioletred", (199, 21, 133)),
+    ("midnightblue", (25, 25, 112)),
+    ("mintcream", (245, 255, 250)),
+    ("mistyrose", (255, 228, 225)),
+    ("moccasin", (255, 228, 181)),
+    ("navajowhite", (255, 222, 173)),
+    ("navy", (0, 0, 128)),
+    ("oldlace", (253, 245, 230)),
+    ("olive", (128, 128, 0)),
+    ("olivedrab", (107, 142, 35)),
+    ("orange", (255, 165, 0)),
+    ("orangered", (255, 69, 0)),
+    ("orchid", (218, 112, 214)),
+    ("palegoldenrod", (238, 232, 170)),
+    ("palegreen", (152, 251, 152)),
+    ("paleturquoise", (175, 238, 238)),
+    ("palevioletred", (219, 112, 147)),
+    ("papayawhip", (255, 239, 213)),
+    ("peachpuff", (255, 218, 185)),
+    ("peru", (205, 133, 63)),
+    ("pink", (255, 192, 203)),
+    ("plum", (221, 160, 221)),
+    ("powderblue", (176, 224, 230)),
+    ("purple", (128, 0, 128)),
+    ("rebeccapurple", (102, 51, 153)),
+    ("rosybrown", (188, 143, 143)),
+    ("royalblue", (65, 105, 225)),
+    ("saddlebrown", (139, 69, 19)),
+    ("salmon", (250, 128, 114)),
+    ("sandybrown", (244, 164, 96)),
+    ("seagreen", (46, 139, 87)),
+    ("seashell", (255, 245, 238)),
+    ("sienna", (160, 82, 45)),
+    ("silver", (192, 192, 192)),
+    ("skyblue", (135, 206, 235)),
+    ("slateblue", (106, 90, 205)),
+    ("slategray", (112, 128, 144)),
+    ("slategrey", (112, 128, 144)),
+    ("snow", (255, 250, 250)),
+    ("springgreen", (0, 255, 127)),
+    ("steelblue", (70, 130, 180)),
+    ("tan", (210, 180, 140)),
+    ("teal", (0, 128, 128)),
+    ("thistle", (216, 191, 216)),
+    ("tomato", (255, 99, 71)),
+    ("turquoise", (64, 224, 208)),
+    ("violet", (238, 130, 238)),
+    ("wheat", (245, 222, 179)),
+    ("whitesmoke", (245, 245, 245)),
+    ("yellowgreen", (154, 205, 50)),
+];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+fn parse_hex(hex: &str) -> Option<(u8, u8, u8)> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+
+    match hex.len() {
+        3 => {
+            let mut channels = hex.chars().map(|c| channel(&c.to_string()).map(|v| v * 17));
+            Some((channels.next()??, channels.next()??, channels.next()??))
+        }
+        6 => Some((
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        )),
+        _ => None,
+    }
+}
+
+impl ColorValue {
+    /// Reads `"#ce422b"`, `"#c42"`, a palette index like `"208"`, an ANSI name like `"bright-red"`
+    /// (following the terminal palette) or any other CSS name like `"tomato"`.
+    ///
+    /// Names ignore case, dashes, underscores and spaces.
+    pub fn parse(value: &str) -> Result<ColorValue, String> {
+        let value = value.trim();
+
+        if let Some(hex) = value.strip_prefix('#') {
+            return parse_hex(hex)
+                .map(|(red, green, blue)| ColorValue::Rgb(red, green, blue))
+                .ok_or_else(|| format!("invalid hex color '{}'", value));
+        }
+
+        if value.chars().all(|c| c.is_ascii_digit()) && !value.is_empty() {
+            return value
+                .parse()
+                .map(ColorValue::Fixed)
+                .map_err(|_| format!("invalid palette index '{}', it goes from 0 to 255", value));
+        }
+
+        let name: String = value
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .collect::<String>()
+            .to_lowercase();
+
+        if let Some(index) = ANSI_NAMES
+            .iter()
+            .position(|ansi| ansi.replace('-', "") == name)
+        {
+            return Ok(ColorValue::Ansi(index as u8));
+        }
+
+        CSS_COLORS
+            .iter()
+            .find(|(css, _)| *css == name)
+            .map(|(_, (red, green, blue))| ColorValue::Rgb(*red, *green, *blue))
+            .ok_or_else(|| format!("unknown color '{}'", value))
+    }
+
+    /// What the color looks like, palette colors being taken from xterm's defaults
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        match *self {
+            ColorValue::Ansi(index) => ANSI_RGB[index as usize % 16],
+            ColorValue::Fixed(index) if index < 16 => ANSI_RGB[index as usize],
+            ColorValue::Fixed(index) if index < 232 => {
+                let index = index - 16;
+                (
+                    CUBE_LEVELS[(index / 36) as usize],
+                    CUBE_LEVELS[(index / 6 % 6) as usize],
+                    CUBE_LEVELS[(index % 6) as usize],
+                )
+            }
+            ColorValue::Fixed(index) => {
+                let level = 8 + 10 * (index - 232);
+                (level, level, level)
+            }
+            ColorValue::Rgb(red, green, blue) => (red, green, blue),
+        }
+    }
+
+    /// The closest color the terminal can show
+    pub fn downgrade(self, support: ColorSupport) -> ColorValue {
+        match (support, self) {
+            (ColorSupport::TrueColor, _) | (_, ColorValue::Ansi(_)) => self,
+            (ColorSupport::Ansi256, ColorValue::Fixed(_)) => self,
+            (ColorSupport::Ansi256, ColorValue::Rgb(red, green, blue)) => {
+                ColorValue::Fixed(closest_256((red, green, blue)))
+            }
+            (ColorSupport::Ansi16, ColorValue::Fixed(index)) if index < 16 => {
+                ColorValue::Ansi(index)
+            }
+            (ColorSupport::Ansi16, _) => ColorValue::Ansi(closest_16(self.to_rgb())),
+        }
+    }
+
+    /// The SGR parameters selecting this color, for the text or for the background
+    pub fn sgr(&self, background: bool) -> String {
+        let offset = if background { 10 } else { 0 };
+        let select = if background { 48 } else { 38 };
+
+        match *self {
+            ColorValue::Ansi(index) if index < 8 => (30 + offset + index).to_string(),
+            ColorValue::Ansi(index) => (90 + offset + index % 16 - 8).to_string(),
+            ColorValue::Fixed(index) => format!("{};5;{}", select, index),
+            ColorValue::Rgb(red, green, blue) => {
+                format!("{};2;{};{};{}", select, red, green, blue)
+            }
+        }
+    }
+}
+
+/// Minimum contrast ratio of the automatic colors against the background, the WCAG one for text
+const MIN_CONTRAST: f64 = 4.5;
+
+/// Relative luminance of a color, as defined by WCAG
+fn luminance(rgb: (u8, u8, u8)) -> f64 {
+    let linear = |channel: u8| {
+        let channel = channel as f64 / 255.0;
+        if channel <= 0.03928 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    0.2126 * linear(rgb.0) + 0.7152 * linear(rgb.1) + 0.0722 * linear(rgb.2)
+}
+
+/// WCAG contrast ratio between two colors, from 1 (the same) to 21 (black on white)
+pub fn contrast_ratio(a: ColorValue, b: ColorValue) -> f64 {
+    let (a, b) = (luminance(a.to_rgb()), luminance(b.to_rgb()));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (red, green, blue) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |value: f64| ((value + lightness - chroma / 2.0) * 255.0).round() as u8;
+
+    (channel(red), channel(green), channel(blue))
+}
+
+/// A color picked from a hash of `key`, so the same key always gets the same color, made
+/// lighter or darker until it's readable on `background`
+pub fn hashed_color(key: &str, background: ColorValue) -> ColorValue {
+    // FNV-1a, which unlike the std hasher is the same on every version and platform
+    let hash = key.bytes().fold(0x811c9dc5_u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    });
+    let hue = (hash % 360) as f64;
+    let saturation = 0.55 + (hash / 360 % 4) as f64 * 0.1;
+    let step = if luminance(background.to_rgb()) < 0.5 {
+        0.05
+    } else {
+        -0.05
+    };
+
+    let mut lightness = 0.6;
+    let mut rgb = hsl_to_rgb(hue, saturation, lightness);
+    while contrast_ratio(ColorValue::Rgb(rgb.0, rgb.1, rgb.2), background) < MIN_CONTRAST
+        && (0.05..=0.95).contains(&(lightness + step))
+    {
+        lightness += step;
+        rgb = hsl_to_rgb(hue, saturation, lightness);
+    }
+
+    ColorValue::Rgb(rgb.0, rgb.1, rgb.2)
+}
+
+fn closest_16(rgb: (u8, u8, u8)) -> u8 {
+    (0..16)
+        .min_by_key(|index| distance(rgb, ANSI_RGB[*index as usize]))
+        .unwrap_or(0)
+}
+
+/// The closest entry of the cube or of the grey ramp, palette colors being left out since
+/// every terminal theme changes them
+fn closest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |channel: u8| {
+        (0..6)
+            .min_by_key(|i| (CUBE_LEVELS[*i] as i32 - channel as i32).abs())
+            .unwrap_or(0) as u8
+    };
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let grey = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    let cube_distance = distance(rgb, ColorValue::Fixed(cube).to_rgb());
+    let grey_distance = distance(rgb, ColorValue::Fixed(grey).to_rgb());
+
+    if grey_distance < cube_distance {
+        grey
+    } else {
+        cube
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors_parse() {
+        assert_eq!(
+            ColorValue::parse("#ce422b"),
+            Ok(ColorValue::Rgb(206, 66, 43))
+        );
+        assert_eq!(ColorValue::parse("#C42"), Ok(ColorValue::Rgb(204, 68, 34)));
+        assert!(ColorValue::parse("#ce42").is_err());
+        assert!(ColorValue::parse("#gg0000").is_err());
+    }
+
+    #[test]
+    fn palette_indexes_and_names_parse() {
+        assert_eq!(ColorValue::parse("208"), Ok(ColorValue::Fixed(208)));
+        assert!(ColorValue::parse("256").is_err());
+
+        assert_eq!(ColorValue::parse("red"), Ok(ColorValue::Ansi(1)));
+        assert_eq!(ColorValue::parse("Bright_Red"), Ok(ColorValue::Ansi(9)));
+        assert_eq!(ColorValue::parse(" bright red "), Ok(ColorValue::Ansi(9)));
+        assert_eq!(
+            ColorValue::parse("tomato"),
+            Ok(ColorValue::Rgb(255, 99, 71))
+        );
+        assert_eq!(
+            ColorValue::parse("Dark-Orange"),
+            Ok(ColorValue::Rgb(255, 140, 0))
+        );
+        assert!(ColorValue::parse("not a color").is_err());
+    }
+
+    #[test]
+    fn fixed_colors_have_their_xterm_rgb() {
+        assert_eq!(ColorValue::Fixed(9).to_rgb(), (255, 0, 0));
+        assert_eq!(ColorValue::Fixed(16).to_rgb(), (0, 0, 0));
+        assert_eq!(ColorValue::Fixed(196).to_rgb(), (255, 0, 0));
+        assert_eq!(ColorValue::Fixed(208).to_rgb(), (255, 135, 0));
+        assert_eq!(ColorValue::Fixed(232).to_rgb(), (8, 8, 8));
+        assert_eq!(ColorValue::Fixed(255).to_rgb(), (238, 238, 238));
+    }
+
+    #[test]
+    fn closest_256_picks_the_cube_or_the_grey_ramp() {
+        assert_eq!(closest_256((255, 0, 0)), 196);
+        assert_eq!(closest_256((0, 0, 0)), 16);
+        assert_eq!(closest_256((128, 128, 128)), 244);
+        assert_eq!(closest_256((250, 130, 10)), 208);
+    }
+
+    #[test]
+    fn colors_downgrade_to_what_the_terminal_shows() {
+        let rgb = ColorValue::Rgb(255, 0, 0);
+        assert_eq!(rgb.downgrade(ColorSupport::TrueColor), rgb);
+        assert_eq!(rgb.downgrade(ColorSupport::Ansi256), ColorValue::Fixed(196));
+        assert_eq!(rgb.downgrade(ColorSupport::Ansi16), ColorValue::Ansi(9));
+
+        assert_eq!(
+            ColorValue::Fixed(208).downgrade(ColorSupport::Ansi256),
+            ColorValue::Fixed(208)
+        );
+        assert_eq!(
+            ColorValue::Fixed(3).downgrade(ColorSupport::Ansi16),
+            ColorValue::Ansi(3)
+        );
+        assert_eq!(
+            ColorValue::Fixed(196).downgrade(ColorSupport::Ansi16),
+            ColorValue::Ansi(9)
+        );
+        // palette colors follow the terminal theme at every level
+        assert_eq!(
+            ColorValue::Ansi(4).downgrade(ColorSupport::Ansi16),
+            ColorValue::Ansi(4)
+        );
+    }
+
+    #[test]
+    fn sgr_selects_the_text_or_the_background() {
+        assert_eq!(ColorValue::Ansi(1).sgr(false), "31");
+        assert_eq!(ColorValue::Ansi(1).sgr(true), "41");
+        assert_eq!(ColorValue::Ansi(9).sgr(false), "91");
+        assert_eq!(ColorValue::Ansi(9).sgr(true), "101");
+        assert_eq!(ColorValue::Fixed(208).sgr(false), "38;5;208");
+        assert_eq!(ColorValue::Fixed(208).sgr(true), "48;5;208");
+        assert_eq!(ColorValue::Rgb(1, 2, 3).sgr(false), "38;2;1;2;3");
+        assert_eq!(ColorValue::Rgb(1, 2, 3).sgr(true), "48;2;1;2;3");
+    }
+
+    #[test]
+    fn hashed_colors_are_stable_and_readable() {
+        let black = ColorValue::Rgb(0, 0, 0);
+        let white = ColorValue::Rgb(255, 255, 255);
+        assert_eq!(contrast_ratio(black, white), 21.0);
+
+        assert_eq!(hashed_color("xyz", black), hashed_color("xyz", black));
+        assert_ne!(hashed_color("xyz", black), hashed_color("abc", black));
+
+        for key in (0..200).map(|i| format!("ext{}", i)) {
+            for background in [black, white, ColorValue::Rgb(40, 42, 54)] {
+                let color = hashed_color(&key, background);
+                assert!(
+                    contrast_ratio(color, background) >= MIN_CONTRAST,
+                    "{:?} on {:?} for {}",
+                    color,
+                    background,
+                    key
+                );
+            }
+        }
+    }
+}
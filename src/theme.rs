@@ -0,0 +1,508 @@
+use colored::Colorize;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde_derive::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+
+use crate::{
+    config::{get_config_colors, Color, Config, DirectoryStyle},
+    ls_colors::{LsColors, LsColorsMode},
+    palette::{hashed_color, ColorValue},
+    types::{
+        entry::Entry,
+        files::{known_file_type, FileType},
+        icons::IconSet,
+    },
+};
+
+/// Themes shipped with lse, a file with the same name in `~/.config/lse/themes/` replaces them
+const BUILTIN_THEMES: [(&str, &str); 4] = [
+    ("dracula", include_str!("themes/dracula.toml")),
+    ("nord", include_str!("themes/nord.toml")),
+    ("gruvbox", include_str!("themes/gruvbox.toml")),
+    ("solarized", include_str!("themes/solarized.toml")),
+];
+
+/// Everything drawn by lse that isn't a file type icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Element {
+    Directory,
+    Symlink,
+    PermissionRead,
+    PermissionWrite,
+    PermissionExecute,
+    PermissionNone,
+    /// The `d` in front of the permissions of a directory
+    PermissionDirectory,
+    /// Background of the directory headers of recursive listings
+    Header,
+    /// Regular files with an execute bit
+    Executable,
+    Fifo,
+    Socket,
+    BlockDevice,
+    CharDevice,
+    /// Names of archives, with `color_names = "kind"`
+    Archive,
+    /// Names of images, audio and video files, with `color_names = "kind"`
+    Media,
+    /// Names of compiled files like `.o` or `.class`, with `color_names = "kind"`
+    Artifact,
+    /// Names of backup files like `notes~` or `.bak`, with `color_names = "kind"`
+    Backup,
+}
+
+impl Element {
+    pub const ALL: [Element; 17] = [
+        Element::Directory,
+        Element::Symlink,
+        Element::PermissionRead,
+        Element::PermissionWrite,
+        Element::PermissionExecute,
+        Element::PermissionNone,
+        Element::PermissionDirectory,
+        Element::Header,
+        Element::Executable,
+        Element::Fifo,
+        Element::Socket,
+        Element::BlockDevice,
+        Element::CharDevice,
+        Element::Archive,
+        Element::Media,
+        Element::Artifact,
+        Element::Backup,
+    ];
+
+    /// The elements drawn with an icon of their own, besides directories
+    pub const KINDS: [Element; 6] = [
+        Element::Symlink,
+        Element::Executable,
+        Element::Fifo,
+        Element::Socket,
+        Element::BlockDevice,
+        Element::CharDevice,
+    ];
+
+    /// Name of the element in the `[elements]` table of a theme file
+    pub fn key(&self) -> &'static str {
+        match self {
+            Element::Directory => "directory",
+            Element::Symlink => "symlink",
+            Element::PermissionRead => "permission-read",
+            Element::PermissionWrite => "permission-write",
+            Element::PermissionExecute => "permission-execute",
+            Element::PermissionNone => "permission-none",
+            Element::PermissionDirectory => "permission-directory",
+            Element::Header => "header",
+            Element::Executable => "executable",
+            Element::Fifo => "fifo",
+            Element::Socket => "socket",
+            Element::BlockDevice => "block-device",
+            Element::CharDevice => "char-device",
+            Element::Archive => "archive",
+            Element::Media => "media",
+            Element::Artifact => "artifact",
+            Element::Backup => "backup",
+        }
+    }
+
+    /// Key of the element in `LS_COLORS`
+    pub fn ls_colors_key(&self) -> Option<&'static str> {
+        match self {
+            Element::Directory => Some("di"),
+            Element::Symlink => Some("ln"),
+            Element::Executable => Some("ex"),
+            Element::Fifo => Some("pi"),
+            Element::Socket => Some("so"),
+            Element::BlockDevice => Some("bd"),
+            Element::CharDevice => Some("cd"),
+            _ => None,
+        }
+    }
+
+    /// How the element looks when the theme doesn't say
+    pub fn default_color(&self) -> Color {
+        let ansi = |index| Color::from(ColorValue::Ansi(index));
+
+        match self {
+            Element::Directory => ansi(4).set_bold(true),
+            Element::Symlink => ansi(2).set_bold(true),
+            Element::PermissionRead => ansi(3),
+            Element::PermissionWrite => ansi(1),
+            Element::PermissionExecute => ansi(2),
+            Element::PermissionNone => Color::new(128, 128, 128),
+            Element::PermissionDirectory => ansi(4),
+            Element::Header => ansi(0).set_background(ColorValue::Ansi(5)).set_bold(true),
+            Element::Executable => ansi(2).set_bold(true),
+            Element::Fifo => ansi(3),
+            Element::Socket => ansi(5).set_bold(true),
+            Element::BlockDevice | Element::CharDevice => ansi(3).set_bold(true),
+            Element::Archive => ansi(1).set_bold(true),
+            Element::Media => ansi(5),
+            Element::Artifact | Element::Backup => ansi(8),
+        }
+    }
+}
+
+/// What a theme file contains, every table is optional
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ThemeFile {
+    /// Colors of the elements, by `Element::key`
+    #[serde(default)]
+    pub elements: HashMap<String, Color>,
+    /// Colors of the file types, same keys as `[colors]` in the config
+    #[serde(default)]
+    pub colors: HashMap<String, Color>,
+    /// Icons of the file types, plus `directory` for directories, the `[icons]` of the config
+    /// win over them
+    #[serde(default)]
+    pub icons: HashMap<String, String>,
+    /// Colors of whole categories, below the ones of single file types
+    #[serde(default)]
+    pub categories: HashMap<String, Color>,
+    /// Background the theme is made for, like `"#282a36"`
+    #[serde(default)]
+    pub background: Option<String>,
+}
+
+/// Names of the themes that can be used without any file
+pub fn builtin_theme_names() -> Vec<&'static str> {
+    BUILTIN_THEMES.iter().map(|(name, _)| *name).collect()
+}
+
+/// Loads `~/.config/lse/themes/<name>.toml`, or the built-in theme of that name
+pub fn load_theme_file(name: &str) -> Result<ThemeFile, String> {
+    if name == "default" {
+        return Ok(ThemeFile::default());
+    }
+
+    let user_theme = env::var("HOME")
+        .ok()
+        .map(|home| format!("{}/.config/lse/themes/{}.toml", home, name));
+
+    let (source, contents) = match user_theme.and_then(|path| {
+        fs::read_to_string(&path)
+            .ok()
+            .map(|contents| (path, contents))
+    }) {
+        Some(found) => found,
+        None => match BUILTIN_THEMES.iter().find(|(builtin, _)| *builtin == name) {
+            Some((_, contents)) => (format!("built-in theme '{}'", name), contents.to_string()),
+            None => {
+                return Err(format!(
+                    "unknown theme '{}', the built-in themes are: default, {}",
+                    name,
+                    builtin_theme_names().join(", ")
+                ))
+            }
+        },
+    };
+
+    let theme_file: ThemeFile =
+        toml::from_str(&contents).map_err(|e| format!("unable to load {}\n{}", source, e))?;
+
+    if let Some(background) = &theme_file.background {
+        ColorValue::parse(background)
+            .map_err(|e| format!("unable to load {}\ninvalid background: {}", source, e))?;
+    }
+
+    Ok(theme_file)
+}
+
+/// Colors and icons of every file type and element, resolved once from the config and theme.
+///
+/// Looking a file type up is then a single map access, instead of going through the config
+/// and its defaults for every file.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    colors: HashMap<FileType, Color>,
+    /// Icons with their color escape sequences already applied, empty when icons are disabled
+    icons: HashMap<FileType, String>,
+    elements: HashMap<Element, Color>,
+    directory_icon: String,
+    /// Icons of `Element::KINDS`, without any color
+    kind_icons: HashMap<Element, String>,
+    /// Directories with their own style, the first matching pattern wins
+    directory_globs: GlobSet,
+    directory_styles: Vec<DirectoryStyle>,
+    glyphs: HashMap<FileType, String>,
+    ls_colors: LsColors,
+    ls_colors_mode: LsColorsMode,
+    /// The background the automatic colors of unknown extensions are made readable on, `None`
+    /// when they are disabled
+    auto_color_background: Option<ColorValue>,
+}
+
+impl Theme {
+    /// The theme from the config alone, without any theme file
+    pub fn new(config: &Config) -> Theme {
+        Theme::with_file(config, &ThemeFile::default())
+    }
+
+    /// The theme from a theme file, falling back on the config, then on the defaults.
+    ///
+    /// A theme is picked on purpose, so its colors win over the `[colors]` of the config.
+    pub fn with_file(config: &Config, theme_file: &ThemeFile) -> Theme {
+        Theme::with_ls_colors(
+            config,
+            theme_file,
+            LsColors::default(),
+            LsColorsMode::Ignore,
+        )
+    }
+
+    /// The theme from a theme file and `LS_COLORS`, combined the way `mode` says
+    pub fn with_ls_colors(
+        config: &Config,
+        theme_file: &ThemeFile,
+        ls_colors: LsColors,
+        mode: LsColorsMode,
+    ) -> Theme {
+        // nothing LS_COLORS sets (kinds of entries, icons, names) is colored without colors
+        let mode = if config.format.colors {
+            mode
+        } else {
+            LsColorsMode::Ignore
+        };
+        let config_colors = get_config_colors(config);
+        let mut colors: HashMap<FileType, Color> = HashMap::new();
+        let mut icons: HashMap<FileType, String> = HashMap::new();
+        let mut glyphs: HashMap<FileType, String> = HashMap::new();
+        let icon_set = config.format.icon_set();
+        let icon_override = |key: &str| {
+            config
+                .icons
+                .get(key)
+                .or(theme_file.icons.get(key))
+                .map(String::as_str)
+        };
+
+        for file_type in FileType::ALL {
+            let key = file_type.config_key();
+            let color = theme_file
+                .colors
+                .get(key)
+                .or(file_type
+                    .legacy_key()
+                    .and_then(|legacy| theme_file.colors.get(legacy)))
+                .or(theme_file.categories.get(file_type.category().config_key()))
+                .or(config_colors.get(key))
+                .copied();
+            let glyph = icon_override(key).unwrap_or(icon_set.glyph(file_type));
+
+            let icon = if icon_set == IconSet::None {
+                String::new()
+            } else {
+                match color {
+                    Some(color) if config.format.colors => color.paint(glyph),
+                    _ => glyph.white().to_string(),
+                }
+            };
+
+            if let Some(color) = color {
+                colors.insert(file_type, color);
+            }
+            icons.insert(file_type, icon);
+            glyphs.insert(file_type, glyph.to_string());
+        }
+
+        // only the colors chosen by the theme or `LS_COLORS`, `element` gives the default ones
+        let elements: HashMap<Element, Color> = Element::ALL
+            .iter()
+            .filter_map(|element| {
+                let from_theme = theme_file.elements.get(element.key());
+                let from_ls_colors = element
+                    .ls_colors_key()
+                    .and_then(|key| ls_colors.kind(key))
+                    .filter(|_| mode != LsColorsMode::Ignore);
+
+                let color = match mode {
+                    LsColorsMode::Prefer => from_ls_colors.or(from_theme),
+                    _ => from_theme.or(from_ls_colors),
+                };
+                color.map(|color| (*element, *color))
+            })
+            .collect();
+
+        // the directories of the config come first, then the ones lse knows, sorted so the
+        // same config always gives the same result. The colors lse gives to the ones it knows
+        // are below a directory color chosen by the theme or `LS_COLORS`.
+        let mut configured: Vec<(&String, &DirectoryStyle)> = config.directories.iter().collect();
+        configured.sort_by_key(|(pattern, _)| *pattern);
+
+        let named_directories =
+            icon_set
+                .named_directories()
+                .into_iter()
+                .map(|(pattern, glyph, (red, green, blue))| {
+                    let style = DirectoryStyle {
+                        icon: glyph.map(str::to_string),
+                        color: (!elements.contains_key(&Element::Directory))
+                            .then(|| Color::new(red, green, blue).set_bold(true)),
+                    };
+                    (pattern.to_string(), style)
+                });
+        let directories: Vec<(String, DirectoryStyle)> = configured
+            .into_iter()
+            .map(|(pattern, style)| (pattern.clone(), style.clone()))
+            .chain(named_directories)
+            .collect();
+
+        // the styles stay in step with the globs, whose indices the matches are given by
+        let mut builder = GlobSetBuilder::new();
+        let mut directory_styles = Vec::new();
+        for (pattern, style) in directories {
+            // a pattern that isn't a valid glob is taken as a plain name
+            let glob = Glob::new(&pattern).or_else(|_| Glob::new(&globset::escape(&pattern)));
+            if let Ok(glob) = glob {
+                builder.add(glob);
+                directory_styles.push(style);
+            }
+        }
+        let directory_globs = builder.build().unwrap_or_else(|_| GlobSet::empty());
+
+        let kind_icons = Element::KINDS
+            .iter()
+            .map(|element| {
+                let glyph = icon_override(element.key()).unwrap_or(icon_set.kind_glyph(*element));
+                (*element, glyph.to_string())
+            })
+            .collect();
+
+        let auto_color_background = config.format.auto_color_unknown.then(|| {
+            config
+                .format
+                .background
+                .as_deref()
+                .or(theme_file.background.as_deref())
+                .and_then(|background| ColorValue::parse(background).ok())
+                .unwrap_or(ColorValue::Rgb(0, 0, 0))
+        });
+
+        Theme {
+            colors,
+            icons,
+            elements,
+            kind_icons,
+            directory_icon: icon_override("directory")
+                .unwrap_or(icon_set.directory_glyph())
+                .to_string(),
+            directory_globs,
+            directory_styles,
+            glyphs,
+            ls_colors,
+            ls_colors_mode: mode,
+            auto_color_background,
+        }
+    }
+
+    pub fn color(&self, file_type: FileType) -> Option<&Color> {
+        self.colors.get(&file_type)
+    }
+
+    pub fn icon(&self, file_type: FileType) -> &str {
+        self.icons.get(&file_type).map(String::as_str).unwrap_or("")
+    }
+
+    /// The color `LS_COLORS` gives to a file, if it's used for it.
+    ///
+    /// Like in GNU ls, the color of executables comes before the one of their suffix. With
+    /// `fallback`, only the files lse doesn't know get their color from `LS_COLORS`.
+    fn ls_color(&self, entry: &Entry) -> Option<&Color> {
+        let name = entry.name.to_string_lossy();
+
+        let skip = match self.ls_colors_mode {
+            LsColorsMode::Ignore => true,
+            LsColorsMode::Fallback => {
+                known_file_type(&name).is_some()
+                    || !matches!(entry.file_type, FileType::None | FileType::Unknown)
+            }
+            LsColorsMode::Prefer => false,
+        };
+        if skip {
+            return None;
+        }
+
+        self.ls_colors
+            .kind("ex")
+            .filter(|_| entry.is_executable())
+            .or_else(|| self.ls_colors.suffix_color(&name))
+    }
+
+    /// The automatic color of a file with an extension lse doesn't know, when enabled
+    fn auto_color(&self, entry: &Entry) -> Option<Color> {
+        let background = self.auto_color_background?;
+        let name = entry.name.to_string_lossy();
+
+        if entry.file_type != FileType::Unknown {
+            return None;
+        }
+
+        let extension = Path::new(name.as_ref()).extension()?.to_string_lossy();
+        Some(Color::from(hashed_color(
+            &extension.to_lowercase(),
+            background,
+        )))
+    }
+
+    /// The color of a file that wins over the one of its type: from `LS_COLORS`, or automatic
+    fn own_color(&self, entry: &Entry) -> Option<Color> {
+        self.ls_color(entry)
+            .copied()
+            .or_else(|| self.auto_color(entry))
+    }
+
+    /// The color of a file, from `LS_COLORS`, automatic, or else from its file type
+    pub fn file_color(&self, entry: &Entry) -> Option<Color> {
+        self.own_color(entry)
+            .or_else(|| self.color(entry.file_type).copied())
+    }
+
+    /// The icon of a file, colored by `LS_COLORS` or automatically when it has such a color
+    pub fn file_icon(&self, entry: &Entry) -> Cow<'_, str> {
+        match (self.own_color(entry), self.glyphs.get(&entry.file_type)) {
+            (Some(color), Some(glyph)) => Cow::Owned(color.paint(glyph)),
+            _ => Cow::Borrowed(self.icon(entry.file_type)),
+        }
+    }
+
+    /// The icon of an element of `Element::KINDS`, without any color
+    pub fn kind_icon(&self, element: Element) -> &str {
+        self.kind_icons
+            .get(&element)
+            .map(String::as_str)
+            .unwrap_or("")
+    }
+
+    /// The icon of a directory without any color, and its color
+    pub fn directory_style(&self, name: &OsStr) -> (&str, Color) {
+        let mut matches = self.directory_globs.matches(name);
+        matches.sort_unstable();
+        let styles = || matches.iter().map(|index| &self.directory_styles[*index]);
+
+        let icon = styles()
+            .find_map(|style| style.icon.as_deref())
+            .unwrap_or(&self.directory_icon);
+        let color = styles()
+            .find_map(|style| style.color)
+            .unwrap_or(self.element(Element::Directory));
+
+        (icon, color)
+    }
+
+    pub fn element(&self, element: Element) -> Color {
+        self.elements
+            .get(&element)
+            .copied()
+            .unwrap_or(element.default_color())
+    }
+
+    /// Colors `text` the way the theme wants `element` to look
+    pub fn paint(&self, element: Element, text: &str) -> String {
+        self.element(element).paint(text)
+    }
+}
@@ -0,0 +1,262 @@
+use flate2::read::GzDecoder;
+use ruzstd::decoding::StreamingDecoder;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tar::EntryType;
+use zip::ZipArchive;
+
+use crate::types::{
+    entry::{Entry, EntryKind, Stat},
+    rules::FileTypeRules,
+};
+
+/// The archives whose content is listed like the one of a directory
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGz,
+    TarZst,
+    Zip,
+}
+
+/// How the names of archives end, in lowercase, and their format
+const ARCHIVE_SUFFIXES: [(&str, ArchiveFormat); 6] = [
+    (".tar", ArchiveFormat::Tar),
+    (".tar.gz", ArchiveFormat::TarGz),
+    (".tgz", ArchiveFormat::TarGz),
+    (".tar.zst", ArchiveFormat::TarZst),
+    (".tzst", ArchiveFormat::TarZst),
+    (".zip", ArchiveFormat::Zip),
+];
+
+impl ArchiveFormat {
+    /// The format of the archive named `name`, `None` when it isn't one lse can read
+    pub fn of(name: &str) -> Option<ArchiveFormat> {
+        let name = name.to_lowercase();
+
+        ARCHIVE_SUFFIXES
+            .iter()
+            .find(|(suffix, _)| name.ends_with(suffix))
+            .map(|(_, format)| *format)
+    }
+}
+
+/// An entry as stored in an archive, before it gets its place in the tree
+struct Archived {
+    path: PathBuf,
+    kind: EntryKind,
+    stat: Stat,
+    symlink_target: Option<PathBuf>,
+}
+
+/// Reads the entries of the archive at `path` without extracting anything.
+///
+/// They come as a tree: the entries of a directory are its children, and directories that
+/// only appear in the paths of other entries are added. Their paths start with `path`.
+pub fn read_archive(
+    path: &Path,
+    format: ArchiveFormat,
+    rules: &FileTypeRules,
+) -> io::Result<Vec<Entry>> {
+    let file = BufReader::new(File::open(path)?);
+    let archived = match format {
+        ArchiveFormat::Tar => read_tar(file)?,
+        ArchiveFormat::TarGz => read_tar(GzDecoder::new(file))?,
+        ArchiveFormat::TarZst => read_tar(StreamingDecoder::new(file).map_err(io::Error::other)?)?,
+        ArchiveFormat::Zip => read_zip(file)?,
+    };
+
+    Ok(into_tree(path, archived, rules))
+}
+
+fn read_tar(reader: impl Read) -> io::Result<Vec<Archived>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut archived = Vec::new();
+
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let header = entry.header();
+        let kind = match header.entry_type() {
+            EntryType::Regular | EntryType::Continuous | EntryType::Link | EntryType::GNUSparse => {
+                EntryKind::File
+            }
+            EntryType::Directory => EntryKind::Directory,
+            EntryType::Symlink => EntryKind::Symlink,
+            EntryType::Fifo => EntryKind::Fifo,
+            EntryType::Block => EntryKind::BlockDevice,
+            EntryType::Char => EntryKind::CharDevice,
+            // headers describing the next entry, or the whole archive
+            _ => continue,
+        };
+        let symlink_target = match kind {
+            EntryKind::Symlink => entry.link_name()?.map(|target| target.into_owned()),
+            _ => None,
+        };
+
+        archived.push(Archived {
+            path: entry.path()?.into_owned(),
+            kind,
+            stat: Stat {
+                size: entry.size(),
+                mode: header.mode()? & 0o7777 | kind.mode_bits(),
+                modified: header
+                    .mtime()
+                    .ok()
+                    .map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds)),
+            },
+            symlink_target,
+        });
+    }
+
+    Ok(archived)
+}
+
+fn read_zip(reader: BufReader<File>) -> io::Result<Vec<Archived>> {
+    let mut archive = ZipArchive::new(reader).map_err(io::Error::other)?;
+    let mut archived = Vec::new();
+
+    for index in 0..archive.len() {
+        let file = archive.by_index_raw(index).map_err(io::Error::other)?;
+        let unix_mode = file.unix_mode();
+        let kind = match unix_mode {
+            Some(mode) if mode & 0o170000 != 0 => EntryKind::from_mode(mode),
+            _ if file.is_dir() => EntryKind::Directory,
+            _ => EntryKind::File,
+        };
+        let permissions = match (unix_mode, kind) {
+            (Some(mode), _) => mode & 0o7777,
+            (None, EntryKind::Directory) => 0o755,
+            (None, _) => 0o644,
+        };
+        let path = PathBuf::from(file.name());
+        let stat = Stat {
+            size: file.size(),
+            mode: permissions | kind.mode_bits(),
+            modified: file.last_modified().and_then(|time| {
+                dos_time_to_system_time(
+                    time.year(),
+                    time.month(),
+                    time.day(),
+                    time.hour(),
+                    time.minute(),
+                    time.second(),
+                )
+            }),
+        };
+        drop(file);
+
+        // the target of a symlink is its content, which may be compressed
+        let symlink_target = if kind == EntryKind::Symlink {
+            let mut target = String::new();
+            archive
+                .by_index(index)
+                .map_err(io::Error::other)?
+                .read_to_string(&mut target)?;
+            Some(PathBuf::from(target))
+        } else {
+            None
+        };
+
+        archived.push(Archived {
+            path,
+            kind,
+            stat,
+            symlink_target,
+        });
+    }
+
+    Ok(archived)
+}
+
+/// The time of a zip entry, which has no time zone, taken as UTC
+fn dos_time_to_system_time(
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+) -> Option<SystemTime> {
+    if !(1..=12).contains(&month) || day == 0 {
+        return None;
+    }
+
+    // days since 1970-01-01 of a date of the proleptic Gregorian calendar
+    let (year, month) = (year as i64, month as i64);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days * 86_400 + hour as i64 * 3_600 + minute as i64 * 60 + second as i64;
+    Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(seconds).ok()?))
+}
+
+/// Nests the entries of an archive under their directories, in the order of the archive
+fn into_tree(archive_path: &Path, archived: Vec<Archived>, rules: &FileTypeRules) -> Vec<Entry> {
+    let mut root: Vec<Entry> = Vec::new();
+
+    for item in archived {
+        // leading `/`, `./` and `..` are left out, like tar does when extracting
+        let components: Vec<OsString> = item
+            .path
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_os_string()),
+                _ => None,
+            })
+            .collect();
+        let Some((name, parents)) = components.split_last() else {
+            continue;
+        };
+
+        let mut entries = &mut root;
+        let mut path = archive_path.to_path_buf();
+        for parent in parents {
+            path.push(parent);
+            let index = match entries
+                .iter()
+                .rposition(|entry| entry.is_dir() && entry.name == *parent)
+            {
+                Some(index) => index,
+                None => {
+                    let stat = Stat {
+                        size: 0,
+                        mode: 0o755 | EntryKind::Directory.mode_bits(),
+                        modified: None,
+                    };
+                    let mut directory =
+                        Entry::with_stat(parent, &path, EntryKind::Directory, stat, rules);
+                    directory.archived = true;
+                    entries.push(directory);
+                    entries.len() - 1
+                }
+            };
+            entries = &mut entries[index].children;
+        }
+        path.push(name);
+
+        // a directory stored after the entries inside it was already added for them
+        if item.kind == EntryKind::Directory {
+            if let Some(directory) = entries
+                .iter_mut()
+                .rfind(|entry| entry.is_dir() && entry.name == *name)
+            {
+                directory.stat = item.stat;
+                continue;
+            }
+        }
+
+        let mut entry = Entry::with_stat(name, path, item.kind, item.stat, rules);
+        entry.symlink_target = item.symlink_target;
+        entry.archived = true;
+        entries.push(entry);
+    }
+
+    root
+}
@@ -0,0 +1,232 @@
+use serde_derive::Serialize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::{self, DirEntry, Metadata};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::types::{
+    archive::ArchiveFormat, files::FileType, image::ImageInfo, media::MediaInfo, mime::mime_of,
+    rules::FileTypeRules,
+};
+
+/// What an entry is on disk, independently of what its name says
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EntryKind {
+    Directory,
+    File,
+    Symlink,
+    /// A named pipe
+    Fifo,
+    Socket,
+    BlockDevice,
+    CharDevice,
+    /// Anything else, like Solaris doors
+    Other,
+}
+
+impl EntryKind {
+    pub fn from_metadata(metadata: &Metadata) -> EntryKind {
+        let file_type = metadata.file_type();
+
+        if file_type.is_symlink() {
+            EntryKind::Symlink
+        } else if file_type.is_dir() {
+            EntryKind::Directory
+        } else if file_type.is_file() {
+            EntryKind::File
+        } else if file_type.is_fifo() {
+            EntryKind::Fifo
+        } else if file_type.is_socket() {
+            EntryKind::Socket
+        } else if file_type.is_block_device() {
+            EntryKind::BlockDevice
+        } else if file_type.is_char_device() {
+            EntryKind::CharDevice
+        } else {
+            EntryKind::Other
+        }
+    }
+
+    /// The kind given by the file type bits of a Unix mode, like the ones stored in archives
+    pub fn from_mode(mode: u32) -> EntryKind {
+        match mode & 0o170000 {
+            0o040000 => EntryKind::Directory,
+            0o100000 => EntryKind::File,
+            0o120000 => EntryKind::Symlink,
+            0o010000 => EntryKind::Fifo,
+            0o140000 => EntryKind::Socket,
+            0o060000 => EntryKind::BlockDevice,
+            0o020000 => EntryKind::CharDevice,
+            _ => EntryKind::Other,
+        }
+    }
+
+    /// The file type bits of a Unix mode for this kind
+    pub fn mode_bits(&self) -> u32 {
+        match self {
+            EntryKind::Directory => 0o040000,
+            EntryKind::File => 0o100000,
+            EntryKind::Symlink => 0o120000,
+            EntryKind::Fifo => 0o010000,
+            EntryKind::Socket => 0o140000,
+            EntryKind::BlockDevice => 0o060000,
+            EntryKind::CharDevice => 0o020000,
+            EntryKind::Other => 0,
+        }
+    }
+
+    /// The `inode/*` MIME type of anything that isn't a regular file
+    pub fn mime(&self) -> &'static str {
+        match self {
+            EntryKind::Directory => "inode/directory",
+            EntryKind::Symlink => "inode/symlink",
+            EntryKind::Fifo => "inode/fifo",
+            EntryKind::Socket => "inode/socket",
+            EntryKind::BlockDevice => "inode/blockdevice",
+            EntryKind::CharDevice => "inode/chardevice",
+            EntryKind::File | EntryKind::Other => "application/octet-stream",
+        }
+    }
+}
+
+/// The part of the metadata the renderers use, which entries inside archives have too
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stat {
+    pub size: u64,
+    /// The Unix mode, file type bits included
+    pub mode: u32,
+    pub modified: Option<SystemTime>,
+}
+
+impl Stat {
+    pub fn from_metadata(metadata: &Metadata) -> Stat {
+        Stat {
+            size: metadata.len(),
+            mode: metadata.permissions().mode(),
+            modified: metadata.modified().ok(),
+        }
+    }
+}
+
+/// A file found while listing a directory, with everything the renderers need to know about it
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub name: OsString,
+    pub path: PathBuf,
+    /// From the metadata of the entry itself, symlinks are not followed
+    pub stat: Stat,
+    pub kind: EntryKind,
+    /// Detected from the name (or the first bytes with `--sniff`), `FileType::None` for
+    /// anything that isn't a regular file
+    pub file_type: FileType,
+    /// The MIME type found along with `file_type` when it was sniffed, see `mime`
+    pub sniffed_mime: Option<&'static str>,
+    /// Where the entry points to, for symlinks
+    pub symlink_target: Option<PathBuf>,
+    /// Extra information given by plugins (like the git status), keyed by plugin name
+    pub plugin_data: HashMap<String, String>,
+    /// Entries of the directory, only filled when listing recursively
+    pub children: Vec<Entry>,
+    /// Dimensions and EXIF data of images, only read when they are shown or sorted by
+    pub image: Option<ImageInfo>,
+    /// Duration, bitrate or resolution and codec of audio and video files, only read when shown
+    pub media: Option<MediaInfo>,
+    /// The format of an archive given on the command line, whose entries are then its children
+    pub archive: Option<ArchiveFormat>,
+    /// Read from inside an archive: its path doesn't exist on disk
+    pub archived: bool,
+}
+
+impl Entry {
+    /// An entry with its file type from the built-in rules
+    pub fn new(name: impl Into<OsString>, path: impl Into<PathBuf>, metadata: Metadata) -> Entry {
+        Entry::with_rules(name, path, metadata, FileTypeRules::builtin())
+    }
+
+    /// An entry with its file type from `rules`
+    pub fn with_rules(
+        name: impl Into<OsString>,
+        path: impl Into<PathBuf>,
+        metadata: Metadata,
+        rules: &FileTypeRules,
+    ) -> Entry {
+        let kind = EntryKind::from_metadata(&metadata);
+        let mut entry = Entry::with_stat(name, path, kind, Stat::from_metadata(&metadata), rules);
+
+        if kind == EntryKind::Symlink {
+            entry.symlink_target = fs::read_link(&entry.path).ok();
+        }
+
+        entry
+    }
+
+    /// An entry that isn't necessarily on disk, like one inside an archive, with its file type
+    /// from `rules`
+    pub fn with_stat(
+        name: impl Into<OsString>,
+        path: impl Into<PathBuf>,
+        kind: EntryKind,
+        stat: Stat,
+        rules: &FileTypeRules,
+    ) -> Entry {
+        let name = name.into();
+        let path = path.into();
+        let (file_type, sniffed_mime) = match kind {
+            EntryKind::File => rules.file_type_at(&name.to_string_lossy(), &path),
+            _ => (FileType::None, None),
+        };
+
+        Entry {
+            name,
+            path,
+            stat,
+            kind,
+            file_type,
+            sniffed_mime,
+            symlink_target: None,
+            plugin_data: HashMap::new(),
+            children: Vec::new(),
+            image: None,
+            media: None,
+            archive: None,
+            archived: false,
+        }
+    }
+
+    pub fn from_dir_entry(entry: &DirEntry, metadata: Metadata, rules: &FileTypeRules) -> Entry {
+        Entry::with_rules(entry.file_name(), entry.path(), metadata, rules)
+    }
+
+    pub fn is_dotfile(&self) -> bool {
+        self.name.as_bytes().starts_with(b".")
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.kind == EntryKind::Directory
+    }
+
+    /// A directory, or an archive listed like one
+    pub fn is_container(&self) -> bool {
+        self.is_dir() || self.archive.is_some()
+    }
+
+    /// The MIME type of a regular file, agreeing with its file type, or `inode/*` for anything
+    /// else
+    pub fn mime(&self) -> &'static str {
+        match self.kind {
+            EntryKind::File => self
+                .sniffed_mime
+                .unwrap_or_else(|| mime_of(&self.name.to_string_lossy(), self.file_type)),
+            kind => kind.mime(),
+        }
+    }
+
+    /// A regular file with any of its execute bits set
+    pub fn is_executable(&self) -> bool {
+        self.kind == EntryKind::File && self.stat.mode & 0o111 != 0
+    }
+}
@@ -1,9 +1,13 @@
-use colored::Colorize;
-use std::path::Path;
+use serde_derive::{Deserialize, Serialize};
+use std::str::FromStr;
 
-use crate::config::{get_config_colors, set_truecolor, Config};
+use crate::{theme::Theme, types::rules::FileTypeRules};
 
+/// Written in the config with its `config_key`, like `"rust"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "&'static str")]
 pub enum FileType {
+    /// Genuine configuration files, like `.ini`, `.conf` or rc-files
     Config,
     Rust,
     C,
@@ -39,72 +43,772 @@ pub enum FileType {
     Java,
     Assembly,
     Twig,
+    Docker,
+    Make,
+    Archive,
+    Shell,
+    Pdf,
+    Text,
+    Binary,
+    Ruby,
+    Swift,
+    Dart,
+    Elixir,
+    Erlang,
+    Haskell,
+    Scala,
+    Perl,
+    R,
+    Julia,
+    OCaml,
+    Clojure,
+    Nim,
+    Nix,
+    Terraform,
+    Sql,
+    Protobuf,
+    GraphQL,
+    Svelte,
+    Vue,
+    Font,
+    Csv,
+    Xml,
+    Tex,
+    Document,
+    Spreadsheet,
+    Presentation,
+    Ebook,
+    Database,
+    Diff,
+    Key,
+    /// Files with an extension lse doesn't know
+    Unknown,
+    /// Files without any extension
     None,
 }
 
-pub fn get_file_type(file: String) -> FileType {
-    let path = Path::new(&file);
-
-    // for specific files/dotfiles
-    if let Some(dotfile) = path.file_name().and_then(|file_name| file_name.to_str()) {
-        match dotfile {
-            ".gitignore" | ".gitmodules" | ".gitattributes" => return FileType::Git,
-            "LICENSE" => return FileType::License,
-            "go.mod" | "go.sum" => return FileType::Golang,
-            ".vimrc" => return FileType::Vim,
-            _ => {}
+impl FileType {
+    /// Every file type, in declaration order
+    pub const ALL: [FileType; 75] = [
+        FileType::Config,
+        FileType::Rust,
+        FileType::C,
+        FileType::CPP,
+        FileType::CS,
+        FileType::Zig,
+        FileType::Python,
+        FileType::JavaScript,
+        FileType::TypeScript,
+        FileType::Html,
+        FileType::Css,
+        FileType::Scss,
+        FileType::Less,
+        FileType::React,
+        FileType::Git,
+        FileType::Lock,
+        FileType::Toml,
+        FileType::License,
+        FileType::Markdown,
+        FileType::Golang,
+        FileType::Svg,
+        FileType::Photo,
+        FileType::Audio,
+        FileType::Video,
+        FileType::Blender,
+        FileType::Lua,
+        FileType::Vim,
+        FileType::Gleam,
+        FileType::Php,
+        FileType::Json,
+        FileType::Yaml,
+        FileType::Kotlin,
+        FileType::Java,
+        FileType::Assembly,
+        FileType::Twig,
+        FileType::Docker,
+        FileType::Make,
+        FileType::Archive,
+        FileType::Shell,
+        FileType::Pdf,
+        FileType::Text,
+        FileType::Binary,
+        FileType::Ruby,
+        FileType::Swift,
+        FileType::Dart,
+        FileType::Elixir,
+        FileType::Erlang,
+        FileType::Haskell,
+        FileType::Scala,
+        FileType::Perl,
+        FileType::R,
+        FileType::Julia,
+        FileType::OCaml,
+        FileType::Clojure,
+        FileType::Nim,
+        FileType::Nix,
+        FileType::Terraform,
+        FileType::Sql,
+        FileType::Protobuf,
+        FileType::GraphQL,
+        FileType::Svelte,
+        FileType::Vue,
+        FileType::Font,
+        FileType::Csv,
+        FileType::Xml,
+        FileType::Tex,
+        FileType::Document,
+        FileType::Spreadsheet,
+        FileType::Presentation,
+        FileType::Ebook,
+        FileType::Database,
+        FileType::Diff,
+        FileType::Key,
+        FileType::Unknown,
+        FileType::None,
+    ];
+
+    /// Name of the file type, as used for the keys of `[colors]` in the config
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            FileType::Config => "config",
+            FileType::Rust => "rust",
+            FileType::C => "c",
+            FileType::CPP => "cpp",
+            FileType::CS => "cs",
+            FileType::Zig => "zig",
+            FileType::Python => "python",
+            FileType::JavaScript => "javascript",
+            FileType::TypeScript => "typescript",
+            FileType::Html => "html",
+            FileType::Css => "css",
+            FileType::Scss => "scss",
+            FileType::Less => "less",
+            FileType::React => "react",
+            FileType::Git => "git",
+            FileType::Lock => "lock",
+            FileType::Toml => "toml",
+            FileType::License => "license",
+            FileType::Markdown => "markdown",
+            FileType::Golang => "golang",
+            FileType::Svg => "svg",
+            FileType::Photo => "photo",
+            FileType::Audio => "audio",
+            FileType::Video => "video",
+            FileType::Blender => "blender",
+            FileType::Lua => "lua",
+            FileType::Vim => "vim",
+            FileType::Gleam => "gleam",
+            FileType::Php => "php",
+            FileType::Json => "json",
+            FileType::Yaml => "yaml",
+            FileType::Kotlin => "kotlin",
+            FileType::Java => "java",
+            FileType::Assembly => "assembly",
+            FileType::Twig => "twig",
+            FileType::Docker => "docker",
+            FileType::Make => "make",
+            FileType::Archive => "archive",
+            FileType::Shell => "shell",
+            FileType::Pdf => "pdf",
+            FileType::Text => "text",
+            FileType::Binary => "binary",
+            FileType::Ruby => "ruby",
+            FileType::Swift => "swift",
+            FileType::Dart => "dart",
+            FileType::Elixir => "elixir",
+            FileType::Erlang => "erlang",
+            FileType::Haskell => "haskell",
+            FileType::Scala => "scala",
+            FileType::Perl => "perl",
+            FileType::R => "r",
+            FileType::Julia => "julia",
+            FileType::OCaml => "ocaml",
+            FileType::Clojure => "clojure",
+            FileType::Nim => "nim",
+            FileType::Nix => "nix",
+            FileType::Terraform => "terraform",
+            FileType::Sql => "sql",
+            FileType::Protobuf => "protobuf",
+            FileType::GraphQL => "graphql",
+            FileType::Svelte => "svelte",
+            FileType::Vue => "vue",
+            FileType::Font => "font",
+            FileType::Csv => "csv",
+            FileType::Xml => "xml",
+            FileType::Tex => "tex",
+            FileType::Document => "document",
+            FileType::Spreadsheet => "spreadsheet",
+            FileType::Presentation => "presentation",
+            FileType::Ebook => "ebook",
+            FileType::Database => "database",
+            FileType::Diff => "diff",
+            FileType::Key => "key",
+            FileType::Unknown => "unknown",
+            FileType::None => "none",
         }
     }
 
-    // for files with normal extensions
-    match path.extension() {
-        Some(ext) => match ext.to_string_lossy().as_ref() {
-            "rs" => FileType::Rust,
-            "c" => FileType::C,
-            "cpp" | "cc" | "cxx" => FileType::CPP,
-            "cs" => FileType::CS,
-            "zig" => FileType::Zig,
-            "py" => FileType::Python,
-            "js" => FileType::JavaScript,
-            "ts" => FileType::TypeScript,
-            "htm" | "html" => FileType::Html,
-            "css" => FileType::Css,
-            "scss" | "sass" => FileType::Scss,
-            "less" => FileType::Less,
-            "jsx" | "tsx" => FileType::React,
-            "lock" => FileType::Lock,
-            "toml" => FileType::Toml,
-            "md" => FileType::Markdown,
-            "go" => FileType::Golang,
-            "svg" => FileType::Svg,
-            "png" | "jpg" | "jpeg" => FileType::Photo,
-            "mp3" | "wma" | "wav" | "voc" | "tta" | "opus" | "mogg" | "oga" | "nmf" | "movpkg"
-            | "mmf" | "m4b" | "m4a" | "iklax" | "flac" | "au" | "aiff" | "aax" | "aac" => {
-                FileType::Audio
+    /// The broad family of the file type, which themes can color at once
+    pub fn category(&self) -> Category {
+        match self {
+            FileType::Rust
+            | FileType::C
+            | FileType::CPP
+            | FileType::CS
+            | FileType::Zig
+            | FileType::Python
+            | FileType::JavaScript
+            | FileType::TypeScript
+            | FileType::Html
+            | FileType::Css
+            | FileType::Scss
+            | FileType::Less
+            | FileType::React
+            | FileType::Golang
+            | FileType::Lua
+            | FileType::Vim
+            | FileType::Gleam
+            | FileType::Php
+            | FileType::Kotlin
+            | FileType::Java
+            | FileType::Assembly
+            | FileType::Twig
+            | FileType::Shell
+            | FileType::Ruby
+            | FileType::Swift
+            | FileType::Dart
+            | FileType::Elixir
+            | FileType::Erlang
+            | FileType::Haskell
+            | FileType::Scala
+            | FileType::Perl
+            | FileType::R
+            | FileType::Julia
+            | FileType::OCaml
+            | FileType::Clojure
+            | FileType::Nim
+            | FileType::Nix
+            | FileType::Terraform
+            | FileType::Sql
+            | FileType::Protobuf
+            | FileType::GraphQL
+            | FileType::Svelte
+            | FileType::Vue
+            | FileType::Diff => Category::Code,
+            FileType::Svg
+            | FileType::Photo
+            | FileType::Audio
+            | FileType::Video
+            | FileType::Blender
+            | FileType::Font => Category::Media,
+            FileType::Markdown
+            | FileType::License
+            | FileType::Pdf
+            | FileType::Text
+            | FileType::Tex
+            | FileType::Document
+            | FileType::Spreadsheet
+            | FileType::Presentation
+            | FileType::Ebook => Category::Document,
+            FileType::Archive => Category::Archive,
+            FileType::Json
+            | FileType::Yaml
+            | FileType::Csv
+            | FileType::Xml
+            | FileType::Database => Category::Data,
+            FileType::Config | FileType::Toml | FileType::Git | FileType::Key => Category::Config,
+            FileType::Docker | FileType::Make | FileType::Lock | FileType::Binary => {
+                Category::Build
             }
-            "webm" | "mkv" | "flv" | "vob" | "ogv" | "ogg" | "rrc" | "gifv" | "mng" | "mov"
-            | "avi" | "qt" | "wmv" | "yuv" | "rm" | "asf" | "amv" | "mp4" | "m4p" | "mpg"
-            | "mp2" | "mpeg" | "mpe" | "mpv" | "m4v" | "svi" | "3gp" | "3g2" | "mxf" | "roq"
-            | "nsv" | "f4v" | "f4p" | "f4a" | "f4b" => FileType::Video,
-            "blend" => FileType::Blender,
-            "lua" => FileType::Lua,
-            "vim" => FileType::Vim,
-            "gleam" => FileType::Gleam,
-            "php" => FileType::Php,
-            "json" => FileType::Json,
-            "yml" | "yaml" => FileType::Yaml,
-            "kot" | "kt" | "kts" => FileType::Kotlin,
-            "jar" | "war" | "ear" | "aar" | "java" | "class" | "properties" => FileType::Java,
-            "asm" | "s" | "S" => FileType::Assembly,
-            "twig" => FileType::Twig,
-            _ => FileType::Config,
-        },
-        None => FileType::None,
+            FileType::Unknown | FileType::None => Category::Other,
+        }
+    }
+
+    /// The key this file type was configured with before it had its own, used when a config
+    /// or theme only has that one
+    pub fn legacy_key(&self) -> Option<&'static str> {
+        match self {
+            FileType::Unknown => Some("config"),
+            _ => None,
+        }
     }
 }
 
-pub fn get_file_type_icon(file_type: FileType, config: &Config) -> String {
-    let fti: &str = match file_type {
+impl FromStr for FileType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        FileType::ALL
+            .into_iter()
+            .find(|file_type| file_type.config_key() == value)
+            .ok_or_else(|| format!("unknown file type '{}'", value))
+    }
+}
+
+impl TryFrom<String> for FileType {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<FileType> for &'static str {
+    fn from(file_type: FileType) -> Self {
+        file_type.config_key()
+    }
+}
+
+/// The family of a file type, the file type itself being its subtype
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Category {
+    Code,
+    Media,
+    Document,
+    Archive,
+    Data,
+    Config,
+    Build,
+    /// Unknown extensions and files without any
+    Other,
+}
+
+impl Category {
+    pub const ALL: [Category; 8] = [
+        Category::Code,
+        Category::Media,
+        Category::Document,
+        Category::Archive,
+        Category::Data,
+        Category::Config,
+        Category::Build,
+        Category::Other,
+    ];
+
+    /// Name of the category, as used for the keys of `[categories]` in the config and themes
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            Category::Code => "code",
+            Category::Media => "media",
+            Category::Document => "document",
+            Category::Archive => "archive",
+            Category::Data => "data",
+            Category::Config => "config",
+            Category::Build => "build",
+            Category::Other => "other",
+        }
+    }
+}
+
+/// A category or a single file type, as given to `--only`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TypeSelector {
+    Category(Category),
+    FileType(FileType),
+}
+
+impl FromStr for TypeSelector {
+    type Err = String;
+
+    /// Categories win over the file types with the same name, which they contain anyway
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some(category) = Category::ALL
+            .into_iter()
+            .find(|category| category.config_key() == value)
+        {
+            return Ok(TypeSelector::Category(category));
+        }
+
+        value
+            .parse()
+            .map(TypeSelector::FileType)
+            .map_err(|_| format!("unknown category or file type '{}'", value))
+    }
+}
+
+impl TypeSelector {
+    pub fn matches(&self, file_type: FileType) -> bool {
+        match self {
+            TypeSelector::Category(category) => file_type.category() == *category,
+            TypeSelector::FileType(expected) => file_type == *expected,
+        }
+    }
+}
+
+/// File types of the names lse knows, whatever their extension
+pub const NAME_TYPES: [(&str, FileType); 13] = [
+    (".gitignore", FileType::Git),
+    (".gitmodules", FileType::Git),
+    (".gitattributes", FileType::Git),
+    ("go.mod", FileType::Golang),
+    ("go.sum", FileType::Golang),
+    (".vimrc", FileType::Vim),
+    ("Cargo.toml", FileType::Rust),
+    ("package.json", FileType::JavaScript),
+    ("tsconfig.json", FileType::TypeScript),
+    ("CMakeLists.txt", FileType::Make),
+    (".dockerignore", FileType::Docker),
+    (".env", FileType::Config),
+    (".editorconfig", FileType::Config),
+];
+
+/// File types of the names lse knows whatever their case, written in lowercase
+pub const LOWERCASE_NAME_TYPES: [(&str, FileType); 17] = [
+    ("license", FileType::License),
+    ("licence", FileType::License),
+    ("copying", FileType::License),
+    ("readme", FileType::Markdown),
+    ("makefile", FileType::Make),
+    ("gnumakefile", FileType::Make),
+    ("justfile", FileType::Make),
+    ("rakefile", FileType::Ruby),
+    ("gemfile", FileType::Ruby),
+    ("podfile", FileType::Ruby),
+    ("brewfile", FileType::Ruby),
+    ("vagrantfile", FileType::Ruby),
+    ("meson.build", FileType::Make),
+    ("dockerfile", FileType::Docker),
+    ("containerfile", FileType::Docker),
+    ("docker-compose.yml", FileType::Docker),
+    ("docker-compose.yaml", FileType::Docker),
+];
+
+/// File types of the globs lse knows
+pub const GLOB_TYPES: [(&str, FileType); 9] = [
+    ("Dockerfile.*", FileType::Docker),
+    ("*.Dockerfile", FileType::Docker),
+    ("docker-compose.*.yml", FileType::Docker),
+    ("docker-compose.*.yaml", FileType::Docker),
+    (".env.*", FileType::Config),
+    ("LICENSE-*", FileType::License),
+    ("LICENSE.*", FileType::License),
+    ("Makefile.*", FileType::Make),
+    (".*rc", FileType::Config),
+];
+
+/// File types of the extensions made of several parts, like `tar.gz`
+pub const COMPOUND_EXTENSION_TYPES: [(&str, FileType); 7] = [
+    ("tar.gz", FileType::Archive),
+    ("tar.xz", FileType::Archive),
+    ("tar.bz2", FileType::Archive),
+    ("tar.zst", FileType::Archive),
+    ("d.ts", FileType::TypeScript),
+    ("min.js", FileType::JavaScript),
+    ("blade.php", FileType::Php),
+];
+
+/// File types and MIME types of the extensions lse knows
+pub const EXTENSION_TYPES: [(&str, FileType, &str); 236] = [
+    ("rs", FileType::Rust, "text/x-rust"),
+    ("c", FileType::C, "text/x-c"),
+    ("cpp", FileType::CPP, "text/x-c++"),
+    ("cc", FileType::CPP, "text/x-c++"),
+    ("cxx", FileType::CPP, "text/x-c++"),
+    ("cs", FileType::CS, "text/x-csharp"),
+    ("zig", FileType::Zig, "text/x-zig"),
+    ("py", FileType::Python, "text/x-python"),
+    ("js", FileType::JavaScript, "text/javascript"),
+    ("ts", FileType::TypeScript, "text/x-typescript"),
+    ("htm", FileType::Html, "text/html"),
+    ("html", FileType::Html, "text/html"),
+    ("css", FileType::Css, "text/css"),
+    ("scss", FileType::Scss, "text/x-scss"),
+    ("sass", FileType::Scss, "text/x-sass"),
+    ("less", FileType::Less, "text/x-less"),
+    ("jsx", FileType::React, "text/jsx"),
+    ("tsx", FileType::React, "text/tsx"),
+    ("lock", FileType::Lock, "text/plain"),
+    ("toml", FileType::Toml, "application/toml"),
+    ("md", FileType::Markdown, "text/markdown"),
+    ("go", FileType::Golang, "text/x-go"),
+    ("svg", FileType::Svg, "image/svg+xml"),
+    ("png", FileType::Photo, "image/png"),
+    ("jpg", FileType::Photo, "image/jpeg"),
+    ("jpeg", FileType::Photo, "image/jpeg"),
+    ("mp3", FileType::Audio, "audio/mpeg"),
+    ("wma", FileType::Audio, "audio/x-ms-wma"),
+    ("wav", FileType::Audio, "audio/wav"),
+    ("voc", FileType::Audio, "audio/x-voc"),
+    ("tta", FileType::Audio, "audio/x-tta"),
+    ("opus", FileType::Audio, "audio/opus"),
+    ("mogg", FileType::Audio, "audio/ogg"),
+    ("oga", FileType::Audio, "audio/ogg"),
+    ("nmf", FileType::Audio, "application/octet-stream"),
+    ("movpkg", FileType::Video, "video/quicktime"),
+    ("mmf", FileType::Audio, "application/vnd.smaf"),
+    ("m4b", FileType::Audio, "audio/mp4"),
+    ("m4a", FileType::Audio, "audio/mp4"),
+    ("iklax", FileType::Audio, "audio/x-iklax"),
+    ("flac", FileType::Audio, "audio/flac"),
+    ("au", FileType::Audio, "audio/basic"),
+    ("aiff", FileType::Audio, "audio/aiff"),
+    ("aax", FileType::Audio, "audio/vnd.audible.aax"),
+    ("aac", FileType::Audio, "audio/aac"),
+    ("webm", FileType::Video, "video/webm"),
+    ("mkv", FileType::Video, "video/x-matroska"),
+    ("flv", FileType::Video, "video/x-flv"),
+    ("vob", FileType::Video, "video/dvd"),
+    ("ogv", FileType::Video, "video/ogg"),
+    ("ogg", FileType::Video, "video/ogg"),
+    ("rrc", FileType::Video, "application/octet-stream"),
+    ("gifv", FileType::Video, "video/mp4"),
+    ("mng", FileType::Video, "video/x-mng"),
+    ("mov", FileType::Video, "video/quicktime"),
+    ("avi", FileType::Video, "video/x-msvideo"),
+    ("qt", FileType::Video, "video/quicktime"),
+    ("wmv", FileType::Video, "video/x-ms-wmv"),
+    ("yuv", FileType::Video, "video/x-raw-yuv"),
+    ("rm", FileType::Video, "application/vnd.rn-realmedia"),
+    ("asf", FileType::Video, "video/x-ms-asf"),
+    ("amv", FileType::Video, "video/x-amv"),
+    ("mp4", FileType::Video, "video/mp4"),
+    ("m4p", FileType::Video, "video/mp4"),
+    ("mpg", FileType::Video, "video/mpeg"),
+    ("mp2", FileType::Audio, "audio/mpeg"),
+    ("mpeg", FileType::Video, "video/mpeg"),
+    ("mpe", FileType::Video, "video/mpeg"),
+    ("mpv", FileType::Video, "video/mpv"),
+    ("m4v", FileType::Video, "video/mp4"),
+    ("svi", FileType::Video, "video/mp4"),
+    ("3gp", FileType::Video, "video/3gpp"),
+    ("3g2", FileType::Video, "video/3gpp2"),
+    ("mxf", FileType::Video, "application/mxf"),
+    ("roq", FileType::Video, "video/x-roq"),
+    ("nsv", FileType::Video, "video/x-nsv"),
+    ("f4v", FileType::Video, "video/x-f4v"),
+    ("f4p", FileType::Video, "video/mp4"),
+    ("f4a", FileType::Audio, "audio/mp4"),
+    ("f4b", FileType::Audio, "audio/mp4"),
+    ("blend", FileType::Blender, "application/x-blender"),
+    ("lua", FileType::Lua, "text/x-lua"),
+    ("vim", FileType::Vim, "text/x-vim"),
+    ("gleam", FileType::Gleam, "text/x-gleam"),
+    ("php", FileType::Php, "application/x-httpd-php"),
+    ("json", FileType::Json, "application/json"),
+    ("yml", FileType::Yaml, "application/yaml"),
+    ("yaml", FileType::Yaml, "application/yaml"),
+    ("kot", FileType::Kotlin, "text/x-kotlin"),
+    ("kt", FileType::Kotlin, "text/x-kotlin"),
+    ("kts", FileType::Kotlin, "text/x-kotlin"),
+    ("jar", FileType::Java, "application/java-archive"),
+    ("war", FileType::Java, "application/java-archive"),
+    ("ear", FileType::Java, "application/java-archive"),
+    ("aar", FileType::Java, "application/java-archive"),
+    ("java", FileType::Java, "text/x-java"),
+    ("class", FileType::Java, "application/java-vm"),
+    ("properties", FileType::Config, "text/x-java-properties"),
+    ("ini", FileType::Config, "text/plain"),
+    ("conf", FileType::Config, "text/plain"),
+    ("cfg", FileType::Config, "text/plain"),
+    ("env", FileType::Config, "text/plain"),
+    ("asm", FileType::Assembly, "text/x-asm"),
+    ("s", FileType::Assembly, "text/x-asm"),
+    ("S", FileType::Assembly, "text/x-asm"),
+    ("twig", FileType::Twig, "text/x-twig"),
+    ("dockerfile", FileType::Docker, "text/x-dockerfile"),
+    ("mk", FileType::Make, "text/x-makefile"),
+    ("mak", FileType::Make, "text/x-makefile"),
+    ("zip", FileType::Archive, "application/zip"),
+    ("tar", FileType::Archive, "application/x-tar"),
+    ("gz", FileType::Archive, "application/gzip"),
+    ("tgz", FileType::Archive, "application/gzip"),
+    ("xz", FileType::Archive, "application/x-xz"),
+    ("bz2", FileType::Archive, "application/x-bzip2"),
+    ("zst", FileType::Archive, "application/zstd"),
+    ("7z", FileType::Archive, "application/x-7z-compressed"),
+    ("rar", FileType::Archive, "application/vnd.rar"),
+    ("sh", FileType::Shell, "text/x-shellscript"),
+    ("bash", FileType::Shell, "text/x-shellscript"),
+    ("zsh", FileType::Shell, "text/x-shellscript"),
+    ("fish", FileType::Shell, "text/x-shellscript"),
+    ("pdf", FileType::Pdf, "application/pdf"),
+    ("txt", FileType::Text, "text/plain"),
+    ("rb", FileType::Ruby, "text/x-ruby"),
+    ("erb", FileType::Ruby, "text/x-ruby"),
+    ("gemspec", FileType::Ruby, "text/x-ruby"),
+    ("rake", FileType::Ruby, "text/x-ruby"),
+    ("ru", FileType::Ruby, "text/x-ruby"),
+    ("swift", FileType::Swift, "text/x-swift"),
+    ("dart", FileType::Dart, "application/vnd.dart"),
+    ("ex", FileType::Elixir, "text/x-elixir"),
+    ("exs", FileType::Elixir, "text/x-elixir"),
+    ("eex", FileType::Elixir, "text/x-elixir"),
+    ("heex", FileType::Elixir, "text/x-elixir"),
+    ("erl", FileType::Erlang, "text/x-erlang"),
+    ("hrl", FileType::Erlang, "text/x-erlang"),
+    ("hs", FileType::Haskell, "text/x-haskell"),
+    ("lhs", FileType::Haskell, "text/x-literate-haskell"),
+    ("scala", FileType::Scala, "text/x-scala"),
+    ("sc", FileType::Scala, "text/x-scala"),
+    ("sbt", FileType::Scala, "text/x-scala"),
+    ("pl", FileType::Perl, "text/x-perl"),
+    ("pm", FileType::Perl, "text/x-perl"),
+    ("r", FileType::R, "text/x-r"),
+    ("rmd", FileType::R, "text/x-r-markdown"),
+    ("jl", FileType::Julia, "text/x-julia"),
+    ("ml", FileType::OCaml, "text/x-ocaml"),
+    ("mli", FileType::OCaml, "text/x-ocaml"),
+    ("clj", FileType::Clojure, "text/x-clojure"),
+    ("cljs", FileType::Clojure, "text/x-clojure"),
+    ("cljc", FileType::Clojure, "text/x-clojure"),
+    ("edn", FileType::Clojure, "application/edn"),
+    ("nim", FileType::Nim, "text/x-nim"),
+    ("nimble", FileType::Nim, "text/x-nim"),
+    ("nix", FileType::Nix, "text/x-nix"),
+    ("tf", FileType::Terraform, "text/x-hcl"),
+    ("tfvars", FileType::Terraform, "text/x-hcl"),
+    ("hcl", FileType::Terraform, "text/x-hcl"),
+    ("sql", FileType::Sql, "application/sql"),
+    ("proto", FileType::Protobuf, "text/x-protobuf"),
+    ("graphql", FileType::GraphQL, "application/graphql"),
+    ("gql", FileType::GraphQL, "application/graphql"),
+    ("svelte", FileType::Svelte, "text/x-svelte"),
+    ("vue", FileType::Vue, "text/x-vue"),
+    ("ttf", FileType::Font, "font/ttf"),
+    ("otf", FileType::Font, "font/otf"),
+    ("woff", FileType::Font, "font/woff"),
+    ("woff2", FileType::Font, "font/woff2"),
+    ("eot", FileType::Font, "application/vnd.ms-fontobject"),
+    ("csv", FileType::Csv, "text/csv"),
+    ("tsv", FileType::Csv, "text/tab-separated-values"),
+    ("xml", FileType::Xml, "application/xml"),
+    ("xsd", FileType::Xml, "application/xml"),
+    ("xsl", FileType::Xml, "application/xslt+xml"),
+    ("plist", FileType::Xml, "application/x-plist"),
+    ("tex", FileType::Tex, "text/x-tex"),
+    ("bib", FileType::Tex, "text/x-bibtex"),
+    ("sty", FileType::Tex, "text/x-tex"),
+    ("cls", FileType::Tex, "text/x-tex"),
+    ("doc", FileType::Document, "application/msword"),
+    (
+        "docx",
+        FileType::Document,
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    (
+        "odt",
+        FileType::Document,
+        "application/vnd.oasis.opendocument.text",
+    ),
+    ("rtf", FileType::Document, "application/rtf"),
+    ("xls", FileType::Spreadsheet, "application/vnd.ms-excel"),
+    (
+        "xlsx",
+        FileType::Spreadsheet,
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    (
+        "ods",
+        FileType::Spreadsheet,
+        "application/vnd.oasis.opendocument.spreadsheet",
+    ),
+    (
+        "ppt",
+        FileType::Presentation,
+        "application/vnd.ms-powerpoint",
+    ),
+    (
+        "pptx",
+        FileType::Presentation,
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    ),
+    (
+        "odp",
+        FileType::Presentation,
+        "application/vnd.oasis.opendocument.presentation",
+    ),
+    ("epub", FileType::Ebook, "application/epub+zip"),
+    ("mobi", FileType::Ebook, "application/x-mobipocket-ebook"),
+    ("azw3", FileType::Ebook, "application/vnd.amazon.ebook"),
+    ("db", FileType::Database, "application/vnd.sqlite3"),
+    ("sqlite", FileType::Database, "application/vnd.sqlite3"),
+    ("sqlite3", FileType::Database, "application/vnd.sqlite3"),
+    ("diff", FileType::Diff, "text/x-diff"),
+    ("patch", FileType::Diff, "text/x-diff"),
+    ("pem", FileType::Key, "application/x-pem-file"),
+    ("crt", FileType::Key, "application/x-x509-ca-cert"),
+    ("cer", FileType::Key, "application/x-x509-ca-cert"),
+    ("key", FileType::Key, "application/x-pem-file"),
+    ("pub", FileType::Key, "text/plain"),
+    ("asc", FileType::Key, "application/pgp-signature"),
+    ("gpg", FileType::Key, "application/pgp-encrypted"),
+    ("p12", FileType::Key, "application/x-pkcs12"),
+    ("webp", FileType::Photo, "image/webp"),
+    ("gif", FileType::Photo, "image/gif"),
+    ("avif", FileType::Photo, "image/avif"),
+    ("heic", FileType::Photo, "image/heic"),
+    ("heif", FileType::Photo, "image/heif"),
+    ("bmp", FileType::Photo, "image/bmp"),
+    ("tif", FileType::Photo, "image/tiff"),
+    ("tiff", FileType::Photo, "image/tiff"),
+    ("ico", FileType::Photo, "image/vnd.microsoft.icon"),
+    ("mjs", FileType::JavaScript, "text/javascript"),
+    ("cjs", FileType::JavaScript, "text/javascript"),
+    ("mts", FileType::TypeScript, "text/x-typescript"),
+    ("cts", FileType::TypeScript, "text/x-typescript"),
+    ("pyi", FileType::Python, "text/x-python"),
+    ("pyw", FileType::Python, "text/x-python"),
+    ("ksh", FileType::Shell, "text/x-shellscript"),
+    ("markdown", FileType::Markdown, "text/markdown"),
+    ("mdx", FileType::Markdown, "text/mdx"),
+    ("jsonc", FileType::Json, "application/json"),
+    ("json5", FileType::Json, "application/json5"),
+    ("tbz2", FileType::Archive, "application/x-bzip2"),
+    ("txz", FileType::Archive, "application/x-xz"),
+    ("lz4", FileType::Archive, "application/x-lz4"),
+    ("lzma", FileType::Archive, "application/x-lzma"),
+    (
+        "deb",
+        FileType::Archive,
+        "application/vnd.debian.binary-package",
+    ),
+    ("rpm", FileType::Archive, "application/x-rpm"),
+    ("log", FileType::Text, "text/plain"),
+    ("hpp", FileType::CPP, "text/x-c++"),
+    ("h", FileType::C, "text/x-c"),
+    ("cmake", FileType::Make, "text/x-cmake"),
+];
+
+/// Extensions of the files left by editors and tools as backups
+pub const BACKUP_EXTENSIONS: [&str; 8] =
+    ["bak", "old", "orig", "rej", "swp", "swo", "tmp", "backup"];
+
+/// Extensions of the files produced by compilers and build tools
+pub const ARTIFACT_EXTENSIONS: [&str; 14] = [
+    "o", "obj", "a", "lib", "so", "dylib", "dll", "class", "pyc", "pyo", "rlib", "rmeta", "lo",
+    "la",
+];
+
+fn extension_of(name: &str) -> Option<String> {
+    let (stem, extension) = name.rsplit_once('.')?;
+    (!stem.is_empty()).then(|| extension.to_lowercase())
+}
+
+/// Backup files, like `notes~`, `#notes#` or `notes.bak`
+pub fn is_backup(name: &str) -> bool {
+    name.ends_with('~')
+        || (name.len() > 1 && name.starts_with('#') && name.ends_with('#'))
+        || extension_of(name)
+            .is_some_and(|extension| BACKUP_EXTENSIONS.contains(&extension.as_str()))
+}
+
+/// Files produced by a build, like `main.o` or `Main.class`
+pub fn is_build_artifact(name: &str) -> bool {
+    extension_of(name).is_some_and(|extension| ARTIFACT_EXTENSIONS.contains(&extension.as_str()))
+}
+
+/// The file type of a name or extension lse knows, `None` when it would only be guessed
+pub fn known_file_type(file: &str) -> Option<FileType> {
+    FileTypeRules::builtin().known(file)
+}
+
+pub fn get_file_type(file: String) -> FileType {
+    FileTypeRules::builtin().file_type(&file)
+}
+
+/// The Nerd Font glyph of a file type, without any color
+pub fn get_file_type_glyph(file_type: FileType) -> &'static str {
+    match file_type {
         FileType::Rust => "",
         FileType::Config => "",
         FileType::C => "",
@@ -140,64 +844,49 @@ pub fn get_file_type_icon(file_type: FileType, config: &Config) -> String {
         FileType::Java => "",
         FileType::Assembly => "",
         FileType::Twig => "",
-        FileType::None => "".into(),
-    };
-    if !config.format.icons {
-        return "".to_string();
-    } else {
-        let fti_colored = if config.format.colors {
-            let config_colors = get_config_colors(&config);
-
-            match file_type {
-                FileType::Rust => set_truecolor(fti, config_colors.get("rust").unwrap()),
-                FileType::Config => set_truecolor(fti, config_colors.get("config").unwrap()),
-                FileType::C => set_truecolor(fti, config_colors.get("c").unwrap()),
-                FileType::CPP => set_truecolor(fti, config_colors.get("cpp").unwrap()),
-                FileType::CS => set_truecolor(fti, config_colors.get("cs").unwrap()),
-                FileType::Zig => set_truecolor(fti, config_colors.get("zig").unwrap()),
-                FileType::Python => set_truecolor(fti, config_colors.get("python").unwrap()),
-                FileType::JavaScript => {
-                    set_truecolor(fti, config_colors.get("javascript").unwrap())
-                }
-                FileType::TypeScript => {
-                    set_truecolor(fti, config_colors.get("typescript").unwrap())
-                }
-                FileType::Html => set_truecolor(fti, config_colors.get("html").unwrap()),
-                FileType::Css => set_truecolor(fti, config_colors.get("css").unwrap()),
-                FileType::Scss => set_truecolor(fti, config_colors.get("scss").unwrap()),
-                FileType::Less => set_truecolor(fti, config_colors.get("less").unwrap()),
-                FileType::React => set_truecolor(fti, config_colors.get("react").unwrap()),
-                FileType::Git => set_truecolor(fti, config_colors.get("git").unwrap()),
-                FileType::Lock => set_truecolor(fti, config_colors.get("lock").unwrap()),
-                FileType::Toml => set_truecolor(fti, config_colors.get("toml").unwrap()),
-                FileType::License => set_truecolor(fti, config_colors.get("license").unwrap()),
-                FileType::Markdown => set_truecolor(fti, config_colors.get("markdown").unwrap()),
-                FileType::Golang => set_truecolor(fti, config_colors.get("golang").unwrap()),
-                FileType::Svg => set_truecolor(fti, config_colors.get("svg").unwrap()),
-                FileType::Photo => set_truecolor(fti, config_colors.get("photo").unwrap()),
-                FileType::Audio => set_truecolor(fti, config_colors.get("audio").unwrap()),
-                FileType::Video => set_truecolor(fti, config_colors.get("video").unwrap()),
-                FileType::Blender => set_truecolor(fti, config_colors.get("blender").unwrap()),
-                FileType::Lua => set_truecolor(fti, config_colors.get("lua").unwrap()),
-                FileType::Vim => set_truecolor(fti, config_colors.get("vim").unwrap()),
-                FileType::Gleam => set_truecolor(fti, config_colors.get("gleam").unwrap()),
-                FileType::Php => set_truecolor(fti, config_colors.get("php").unwrap()),
-                FileType::Json => set_truecolor(fti, config_colors.get("json").unwrap()),
-                FileType::Yaml => set_truecolor(fti, config_colors.get("yaml").unwrap()),
-                FileType::Kotlin => set_truecolor(fti, config_colors.get("kotlin").unwrap()),
-                FileType::Java => set_truecolor(fti, config_colors.get("java").unwrap()),
-                FileType::Assembly => set_truecolor(fti, config_colors.get("assembly").unwrap()),
-                FileType::Twig => set_truecolor(fti, config_colors.get("twig").unwrap()),
-                _ => fti.white(),
-            }
-        } else {
-            fti.white()
-        };
-
-        fti_colored.to_string()
+        FileType::Docker => "",
+        FileType::Make => "",
+        FileType::Archive => "",
+        FileType::Shell => "",
+        FileType::Pdf => "",
+        FileType::Text => "",
+        FileType::Binary => "",
+        FileType::Ruby => "",
+        FileType::Swift => "",
+        FileType::Dart => "",
+        FileType::Elixir => "",
+        FileType::Erlang => "",
+        FileType::Haskell => "",
+        FileType::Scala => "",
+        FileType::Perl => "",
+        FileType::R => "󰟔",
+        FileType::Julia => "",
+        FileType::OCaml => "",
+        FileType::Clojure => "",
+        FileType::Nim => "",
+        FileType::Nix => "",
+        FileType::Terraform => "󱁢",
+        FileType::Sql => "",
+        FileType::Protobuf => "",
+        FileType::GraphQL => "",
+        FileType::Svelte => "",
+        FileType::Vue => "󰡄",
+        FileType::Font => "",
+        FileType::Csv => "",
+        FileType::Xml => "󰗀",
+        FileType::Tex => "",
+        FileType::Document => "",
+        FileType::Spreadsheet => "",
+        FileType::Presentation => "",
+        FileType::Ebook => "",
+        FileType::Database => "",
+        FileType::Diff => "",
+        FileType::Key => "",
+        FileType::Unknown => "",
+        FileType::None => "",
     }
 }
 
-pub fn render_file(file_name: String, file_type: FileType, config: &Config) -> String {
-    format!("{} {}", get_file_type_icon(file_type, config), file_name)
+pub fn render_file(file_name: String, file_type: FileType, theme: &Theme) -> String {
+    format!("{} {}", theme.icon(file_type), file_name)
 }
@@ -0,0 +1,300 @@
+use serde_derive::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::{
+    theme::Element,
+    types::files::{get_file_type_glyph, FileType},
+};
+
+/// Red, green and blue
+pub type Rgb = (u8, u8, u8);
+
+/// Directories known by name (or glob): pattern, Nerd Font glyph, emoji and color
+pub const NAMED_DIRECTORIES: [(&str, &str, &str, Rgb); 21] = [
+    (".git", "", "🌱", (241, 78, 50)),
+    (".github", "󰊤", "🐙", (137, 87, 229)),
+    (".config", "", "🔧", (109, 128, 134)),
+    (".vscode", "", "🔷", (0, 122, 204)),
+    (".cargo", "", "🦀", (206, 66, 43)),
+    ("node_modules", "", "📦", (139, 195, 74)),
+    ("src", "", "🧩", (86, 156, 214)),
+    ("target", "", "📦", (128, 128, 128)),
+    ("build", "", "📦", (128, 128, 128)),
+    ("dist", "", "📦", (128, 128, 128)),
+    ("tests", "", "🧪", (255, 179, 0)),
+    ("test", "", "🧪", (255, 179, 0)),
+    ("docs", "", "📚", (0, 150, 136)),
+    ("doc", "", "📚", (0, 150, 136)),
+    ("Desktop", "", "💻", (100, 149, 237)),
+    ("Documents", "", "📚", (0, 150, 136)),
+    ("Downloads", "", "📥", (76, 175, 80)),
+    ("Music", "", "🎵", (233, 30, 99)),
+    ("Pictures", "", "📷", (171, 71, 188)),
+    ("Videos", "", "🎬", (255, 112, 67)),
+    ("*.egg-info", "", "📦", (128, 128, 128)),
+];
+
+/// Which glyphs the icons are drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconSet {
+    /// Nerd Font glyphs, which need a patched font
+    #[default]
+    Nerd,
+    /// Emoji, shown by most fonts
+    Emoji,
+    /// Short ASCII tags like `rs` or `py`, shown everywhere
+    Ascii,
+    /// No icons at all
+    None,
+}
+
+impl FromStr for IconSet {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "nerd" => Ok(IconSet::Nerd),
+            "emoji" => Ok(IconSet::Emoji),
+            "ascii" => Ok(IconSet::Ascii),
+            "none" => Ok(IconSet::None),
+            _ => Err(format!(
+                "invalid argument '{}' for '--icon', valid arguments are 'nerd', 'emoji', 'ascii' and 'none'",
+                value
+            )),
+        }
+    }
+}
+
+impl IconSet {
+    /// The icon of a file type in this set
+    pub fn glyph(&self, file_type: FileType) -> &'static str {
+        match self {
+            IconSet::Nerd => get_file_type_glyph(file_type),
+            IconSet::Emoji => emoji_glyph(file_type),
+            IconSet::Ascii => ascii_glyph(file_type),
+            IconSet::None => "",
+        }
+    }
+
+    /// The icon of directories in this set
+    pub fn directory_glyph(&self) -> &'static str {
+        match self {
+            IconSet::Nerd => "",
+            IconSet::Emoji => "📁",
+            IconSet::Ascii => "dir",
+            IconSet::None => "",
+        }
+    }
+
+    /// The icon of an element of `Element::KINDS` in this set
+    pub fn kind_glyph(&self, element: Element) -> &'static str {
+        match self {
+            IconSet::Nerd => match element {
+                Element::Symlink => "",
+                Element::Executable => "",
+                Element::Fifo => "",
+                Element::Socket => "",
+                Element::BlockDevice => "",
+                Element::CharDevice => "",
+                _ => "",
+            },
+            IconSet::Emoji => match element {
+                Element::Symlink => "🔗",
+                Element::Executable => "⚡",
+                Element::Fifo => "🚰",
+                Element::Socket => "🔌",
+                Element::BlockDevice => "💽",
+                Element::CharDevice => "📟",
+                _ => "",
+            },
+            IconSet::Ascii => match element {
+                Element::Symlink => "lnk",
+                Element::Executable => "exe",
+                Element::Fifo => "fif",
+                Element::Socket => "sck",
+                Element::BlockDevice => "blk",
+                Element::CharDevice => "chr",
+                _ => "",
+            },
+            IconSet::None => "",
+        }
+    }
+
+    /// The directories known by name (or glob), with their icon in this set if it has one, and
+    /// their color
+    pub fn named_directories(&self) -> Vec<(&'static str, Option<&'static str>, Rgb)> {
+        NAMED_DIRECTORIES
+            .iter()
+            .map(|(pattern, nerd, emoji, rgb)| {
+                let glyph = match self {
+                    IconSet::Nerd => Some(*nerd),
+                    IconSet::Emoji => Some(*emoji),
+                    _ => None,
+                };
+                (*pattern, glyph, *rgb)
+            })
+            .collect()
+    }
+}
+
+fn emoji_glyph(file_type: FileType) -> &'static str {
+    match file_type {
+        FileType::Config => "🔧",
+        FileType::Rust => "🦀",
+        FileType::C => "🔵",
+        FileType::CPP => "🔷",
+        FileType::CS => "🟣",
+        FileType::Zig => "⚡",
+        FileType::Python => "🐍",
+        FileType::JavaScript => "🟨",
+        FileType::TypeScript => "🟦",
+        FileType::Html => "🌐",
+        FileType::Css => "🎨",
+        FileType::Scss => "🎨",
+        FileType::Less => "🎨",
+        FileType::React => "🌀",
+        FileType::Git => "🌱",
+        FileType::Lock => "🔒",
+        FileType::Toml => "🔧",
+        FileType::License => "📜",
+        FileType::Markdown => "📝",
+        FileType::Golang => "🐹",
+        FileType::Svg => "🎨",
+        FileType::Photo => "📷",
+        FileType::Audio => "🎵",
+        FileType::Video => "🎬",
+        FileType::Blender => "🧊",
+        FileType::Lua => "🌙",
+        FileType::Vim => "📗",
+        FileType::Gleam => "⭐",
+        FileType::Php => "🐘",
+        FileType::Json => "🧾",
+        FileType::Yaml => "📋",
+        FileType::Kotlin => "🟪",
+        FileType::Java => "☕",
+        FileType::Assembly => "🔩",
+        FileType::Twig => "🌿",
+        FileType::Docker => "🐳",
+        FileType::Make => "🔨",
+        FileType::Archive => "📦",
+        FileType::Shell => "🐚",
+        FileType::Pdf => "📕",
+        FileType::Text => "📃",
+        FileType::Binary => "💾",
+        FileType::Ruby => "💎",
+        FileType::Swift => "🐦",
+        FileType::Dart => "🎯",
+        FileType::Elixir => "💧",
+        FileType::Erlang => "📞",
+        FileType::Haskell => "🎓",
+        FileType::Scala => "🔺",
+        FileType::Perl => "🐪",
+        FileType::R => "📈",
+        FileType::Julia => "🔮",
+        FileType::OCaml => "🐫",
+        FileType::Clojure => "🟢",
+        FileType::Nim => "👑",
+        FileType::Nix => "🔹",
+        FileType::Terraform => "🌍",
+        FileType::Sql => "🧮",
+        FileType::Protobuf => "📨",
+        FileType::GraphQL => "🔗",
+        FileType::Svelte => "🔥",
+        FileType::Vue => "💚",
+        FileType::Font => "🔤",
+        FileType::Csv => "📊",
+        FileType::Xml => "📰",
+        FileType::Tex => "📐",
+        FileType::Document => "📘",
+        FileType::Spreadsheet => "📒",
+        FileType::Presentation => "📙",
+        FileType::Ebook => "📖",
+        FileType::Database => "💿",
+        FileType::Diff => "🩹",
+        FileType::Key => "🔑",
+        FileType::Unknown => "❓",
+        FileType::None => "📄",
+    }
+}
+
+/// ASCII tags are padded to 3 characters so the names stay aligned
+fn ascii_glyph(file_type: FileType) -> &'static str {
+    match file_type {
+        FileType::Config => "cfg",
+        FileType::Rust => "rs ",
+        FileType::C => "c  ",
+        FileType::CPP => "c++",
+        FileType::CS => "c# ",
+        FileType::Zig => "zig",
+        FileType::Python => "py ",
+        FileType::JavaScript => "js ",
+        FileType::TypeScript => "ts ",
+        FileType::Html => "htm",
+        FileType::Css => "css",
+        FileType::Scss => "css",
+        FileType::Less => "css",
+        FileType::React => "jsx",
+        FileType::Git => "git",
+        FileType::Lock => "lck",
+        FileType::Toml => "cfg",
+        FileType::License => "lic",
+        FileType::Markdown => "md ",
+        FileType::Golang => "go ",
+        FileType::Svg => "svg",
+        FileType::Photo => "img",
+        FileType::Audio => "snd",
+        FileType::Video => "vid",
+        FileType::Blender => "3d ",
+        FileType::Lua => "lua",
+        FileType::Vim => "vim",
+        FileType::Gleam => "glm",
+        FileType::Php => "php",
+        FileType::Json => "jsn",
+        FileType::Yaml => "yml",
+        FileType::Kotlin => "kt ",
+        FileType::Java => "jav",
+        FileType::Assembly => "asm",
+        FileType::Twig => "twg",
+        FileType::Docker => "dkr",
+        FileType::Make => "mk ",
+        FileType::Archive => "zip",
+        FileType::Shell => "sh ",
+        FileType::Pdf => "pdf",
+        FileType::Text => "txt",
+        FileType::Binary => "bin",
+        FileType::Ruby => "rb ",
+        FileType::Swift => "swf",
+        FileType::Dart => "drt",
+        FileType::Elixir => "ex ",
+        FileType::Erlang => "erl",
+        FileType::Haskell => "hs ",
+        FileType::Scala => "scl",
+        FileType::Perl => "pl ",
+        FileType::R => "r  ",
+        FileType::Julia => "jl ",
+        FileType::OCaml => "ml ",
+        FileType::Clojure => "clj",
+        FileType::Nim => "nim",
+        FileType::Nix => "nix",
+        FileType::Terraform => "tf ",
+        FileType::Sql => "sql",
+        FileType::Protobuf => "pb ",
+        FileType::GraphQL => "gql",
+        FileType::Svelte => "svl",
+        FileType::Vue => "vue",
+        FileType::Font => "fnt",
+        FileType::Csv => "csv",
+        FileType::Xml => "xml",
+        FileType::Tex => "tex",
+        FileType::Document => "doc",
+        FileType::Spreadsheet => "xls",
+        FileType::Presentation => "ppt",
+        FileType::Ebook => "bk ",
+        FileType::Database => "db ",
+        FileType::Diff => "dif",
+        FileType::Key => "key",
+        FileType::Unknown => "?  ",
+        FileType::None => "-  ",
+    }
+}
@@ -0,0 +1,435 @@
+use exif::{In, Reader, Tag, Value};
+use serde_derive::Serialize;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::types::files::FileType;
+
+/// How much of an SVG file is read to find the size of its root element
+const SVG_HEADER_LENGTH: u64 = 4096;
+/// The largest `meta` box of a HEIF or AVIF file that gets read, they are a few kB at most
+const MAX_META_LENGTH: u64 = 1 << 20;
+
+/// What the headers of an image say about it (`--image`)
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ImageInfo {
+    /// Width and height in pixels, as the image is shown: EXIF rotations are applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<(u32, u32)>,
+    /// When the photo was taken according to its EXIF data, as `YYYY-MM-DD HH:MM:SS`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub captured: Option<String>,
+    /// The make and model of the camera, from the EXIF data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub camera: Option<String>,
+}
+
+/// Reads the dimensions of the image at `path` and, for JPEG and HEIF files, its EXIF data.
+///
+/// Only the headers are read: the pixels are never decoded, and the segments and boxes that
+/// don't matter are skipped. `None` when it can't be read or isn't an image lse knows.
+pub fn read_image_info(path: &Path, file_type: FileType) -> Option<ImageInfo> {
+    let mut file = BufReader::new(File::open(path).ok()?);
+
+    if file_type == FileType::Svg {
+        return Some(ImageInfo {
+            dimensions: svg_dimensions(&mut file),
+            ..ImageInfo::default()
+        });
+    }
+
+    let mut header = [0; 32];
+    let length = read_up_to(&mut file, &mut header).ok()?;
+    let header = &header[..length];
+
+    let info = if header.starts_with(b"\x89PNG\r\n\x1a\n") {
+        ImageInfo {
+            dimensions: Some((be32(header, 16)?, be32(header, 20)?)),
+            ..ImageInfo::default()
+        }
+    } else if header.starts_with(b"\xff\xd8") {
+        read_jpeg(&mut file)?
+    } else if header.starts_with(b"GIF8") {
+        ImageInfo {
+            dimensions: Some((le16(header, 6)? as u32, le16(header, 8)? as u32)),
+            ..ImageInfo::default()
+        }
+    } else if header.starts_with(b"RIFF") && header.get(8..12) == Some(b"WEBP") {
+        ImageInfo {
+            dimensions: webp_dimensions(header),
+            ..ImageInfo::default()
+        }
+    } else if header.starts_with(b"BM") {
+        ImageInfo {
+            dimensions: bmp_dimensions(header),
+            ..ImageInfo::default()
+        }
+    } else if header.starts_with(b"\x00\x00\x01\x00") {
+        // a 0 in the first icon of the directory means 256 pixels
+        let size = |byte: u8| if byte == 0 { 256 } else { byte as u32 };
+        ImageInfo {
+            dimensions: Some((size(*header.get(6)?), size(*header.get(7)?))),
+            ..ImageInfo::default()
+        }
+    } else if header.starts_with(b"II*\x00") || header.starts_with(b"MM\x00*") {
+        ImageInfo {
+            dimensions: tiff_dimensions(&mut file, header.starts_with(b"II")),
+            ..ImageInfo::default()
+        }
+    } else if header.get(4..8) == Some(b"ftyp") {
+        read_heif(&mut file)?
+    } else {
+        return None;
+    };
+
+    Some(info)
+}
+
+/// Reads until `buffer` is full or the end of the file, and returns how much was read
+fn read_up_to(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut length = 0;
+
+    while length < buffer.len() {
+        match reader.read(&mut buffer[length..])? {
+            0 => break,
+            read => length += read,
+        }
+    }
+
+    Ok(length)
+}
+
+fn be16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn le16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn le32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Walks the segments of a JPEG file up to its first scan, for the size of its frame and its
+/// EXIF data
+fn read_jpeg(file: &mut BufReader<File>) -> Option<ImageInfo> {
+    let mut dimensions = None;
+    let mut exif = None;
+    file.seek(SeekFrom::Start(2)).ok()?;
+
+    while dimensions.is_none() {
+        let mut marker = [0; 2];
+        file.read_exact(&mut marker).ok()?;
+        if marker[0] != 0xff {
+            return None;
+        }
+
+        match marker[1] {
+            // fill bytes, and markers without a segment
+            0xff => {
+                file.seek_relative(-1).ok()?;
+                continue;
+            }
+            0x01 | 0xd0..=0xd7 => continue,
+            // the start of the scan or the end of the image: no frame header before
+            0xd9 | 0xda => break,
+            _ => {}
+        }
+
+        let mut length = [0; 2];
+        file.read_exact(&mut length).ok()?;
+        let length = (u16::from_be_bytes(length) as usize).checked_sub(2)?;
+
+        match marker[1] {
+            // start of frame, except for DHT, JPG and DAC which share the range
+            0xc0..=0xcf if !matches!(marker[1], 0xc4 | 0xc8 | 0xcc) => {
+                let mut frame = [0; 5];
+                file.read_exact(&mut frame).ok()?;
+                let height = be16(&frame, 1)? as u32;
+                let width = be16(&frame, 3)? as u32;
+                dimensions = Some((width, height));
+            }
+            0xe1 if exif.is_none() => {
+                let mut segment = vec![0; length];
+                file.read_exact(&mut segment).ok()?;
+                if let Some(raw) = segment.strip_prefix(b"Exif\x00\x00") {
+                    exif = Reader::new().read_raw(raw.to_vec()).ok();
+                }
+            }
+            _ => file.seek_relative(length as i64).ok()?,
+        }
+    }
+
+    Some(with_exif(dimensions, exif.as_ref()))
+}
+
+/// The dimensions from the `ispe` boxes of a HEIF or AVIF file, and its EXIF data
+fn read_heif(file: &mut BufReader<File>) -> Option<ImageInfo> {
+    file.seek(SeekFrom::Start(0)).ok()?;
+    let length = file.get_ref().metadata().ok()?.len();
+    let mut dimensions = None;
+
+    // the file is a list of boxes: `ftyp`, `meta` with the properties of the images, `mdat`...
+    loop {
+        let mut header = [0; 8];
+        if file.read_exact(&mut header).is_err() {
+            break;
+        }
+        let mut size = be32(&header, 0)? as u64;
+        // a size of 0 means the box goes on until the end of the file
+        if size == 0 {
+            break;
+        }
+        let mut header_length = 8;
+        if size == 1 {
+            let mut large_size = [0; 8];
+            file.read_exact(&mut large_size).ok()?;
+            size = u64::from_be_bytes(large_size);
+            header_length = 16;
+        }
+        let content_length = size.checked_sub(header_length)?;
+
+        if &header[4..8] == b"meta" {
+            if content_length > MAX_META_LENGTH {
+                return None;
+            }
+            let mut meta = vec![0; content_length as usize];
+            file.read_exact(&mut meta).ok()?;
+            // `meta` starts with a version and flags
+            dimensions = largest_ispe(meta.get(4..)?);
+            break;
+        }
+
+        // a box going past the end of the file is malformed, and its size would not fit a seek
+        let position = file.stream_position().ok()?;
+        if content_length > length.saturating_sub(position) {
+            break;
+        }
+        file.seek_relative(content_length as i64).ok()?;
+    }
+
+    file.seek(SeekFrom::Start(0)).ok()?;
+    let exif = Reader::new().read_from_container(file).ok();
+
+    Some(with_exif(dimensions, exif.as_ref()))
+}
+
+/// The largest image spatial extents found in the `iprp/ipco` boxes of a `meta` box: the size
+/// of the primary image, thumbnails and grid tiles being smaller
+fn largest_ispe(mut boxes: &[u8]) -> Option<(u32, u32)> {
+    let mut largest: Option<(u32, u32)> = None;
+
+    while boxes.len() >= 8 {
+        // a size of 0 means the box goes on until the end
+        let size = match be32(boxes, 0)? as usize {
+            0 => boxes.len(),
+            size if size < 8 => break,
+            size => size.min(boxes.len()),
+        };
+        let content = &boxes[8..size];
+
+        let found = match &boxes[4..8] {
+            b"iprp" | b"ipco" => largest_ispe(content),
+            // a version and flags, then the width and height
+            b"ispe" => Some((be32(content, 4)?, be32(content, 8)?)),
+            _ => None,
+        };
+        if let Some((width, height)) = found {
+            if largest.is_none_or(|(w, h)| width as u64 * height as u64 > w as u64 * h as u64) {
+                largest = Some((width, height));
+            }
+        }
+
+        boxes = &boxes[size..];
+    }
+
+    largest
+}
+
+fn webp_dimensions(header: &[u8]) -> Option<(u32, u32)> {
+    match header.get(12..16)? {
+        // extended format: the canvas size minus one, on 24 bits
+        b"VP8X" => {
+            let width = le32(header, 24)? & 0xff_ffff;
+            let height = le32(header, 26)? >> 8;
+            Some((width + 1, height + 1))
+        }
+        // lossless: 14 bits each, after the 0x2f signature
+        b"VP8L" => {
+            let bits = le32(header, 21)?;
+            Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+        }
+        // lossy: after the frame tag and the start code
+        b"VP8 " => Some((
+            (le16(header, 26)? & 0x3fff) as u32,
+            (le16(header, 28)? & 0x3fff) as u32,
+        )),
+        _ => None,
+    }
+}
+
+fn bmp_dimensions(header: &[u8]) -> Option<(u32, u32)> {
+    // the old OS/2 header has 16 bits sizes, the others 32 bits ones, negative when top-down
+    if le32(header, 14)? == 12 {
+        Some((le16(header, 18)? as u32, le16(header, 20)? as u32))
+    } else {
+        let width = le32(header, 18)? as i32;
+        let height = le32(header, 22)? as i32;
+        Some((width.unsigned_abs(), height.unsigned_abs()))
+    }
+}
+
+/// The `ImageWidth` and `ImageLength` of the first image of a TIFF file
+fn tiff_dimensions(file: &mut BufReader<File>, little_endian: bool) -> Option<(u32, u32)> {
+    let u16_at = |bytes: &[u8], at| {
+        if little_endian {
+            le16(bytes, at)
+        } else {
+            be16(bytes, at)
+        }
+    };
+    let u32_at = |bytes: &[u8], at| {
+        if little_endian {
+            le32(bytes, at)
+        } else {
+            be32(bytes, at)
+        }
+    };
+
+    let mut header = [0; 8];
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_exact(&mut header).ok()?;
+    file.seek(SeekFrom::Start(u32_at(&header, 4)? as u64))
+        .ok()?;
+
+    let mut count = [0; 2];
+    file.read_exact(&mut count).ok()?;
+    let mut ifd = vec![0; u16_at(&count, 0)? as usize * 12];
+    file.read_exact(&mut ifd).ok()?;
+
+    let mut width = None;
+    let mut height = None;
+    for field in ifd.chunks_exact(12) {
+        // SHORT or LONG values fit in the field itself
+        let value = match u16_at(field, 2)? {
+            3 => u16_at(field, 8)? as u32,
+            4 => u32_at(field, 8)?,
+            _ => continue,
+        };
+        match u16_at(field, 0)? {
+            256 => width = Some(value),
+            257 => height = Some(value),
+            _ => {}
+        }
+    }
+
+    Some((width?, height?))
+}
+
+/// The size of the root `<svg>` element: its `width` and `height` in pixels, or else the size of
+/// its `viewBox`
+fn svg_dimensions(file: &mut BufReader<File>) -> Option<(u32, u32)> {
+    let mut header = Vec::new();
+    file.take(SVG_HEADER_LENGTH).read_to_end(&mut header).ok()?;
+    let header = String::from_utf8_lossy(&header);
+
+    let start = header.find("<svg")?;
+    let tag = &header[start..];
+    let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+
+    let attribute = |name: &str| {
+        let mut rest = tag;
+        while let Some(index) = rest.find(name) {
+            let before = rest[..index].chars().last();
+            rest = &rest[index + name.len()..];
+            if !before.is_some_and(char::is_whitespace) {
+                continue;
+            }
+            let value = rest.trim_start().strip_prefix('=')?.trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let value = &value[1..];
+            return Some(&value[..value.find(quote)?]);
+        }
+        None
+    };
+    let pixels = |value: &str| {
+        let value = value.trim();
+        let value = value.strip_suffix("px").unwrap_or(value);
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|pixels| *pixels > 0.0)
+            .map(|pixels| pixels.round() as u32)
+    };
+
+    if let (Some(width), Some(height)) = (
+        attribute("width").and_then(pixels),
+        attribute("height").and_then(pixels),
+    ) {
+        return Some((width, height));
+    }
+
+    let view_box: Vec<&str> = attribute("viewBox")?
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|part| !part.is_empty())
+        .collect();
+    Some((pixels(view_box.get(2)?)?, pixels(view_box.get(3)?)?))
+}
+
+/// `dimensions` rotated as the EXIF orientation says, with the capture date and camera
+fn with_exif(dimensions: Option<(u32, u32)>, exif: Option<&exif::Exif>) -> ImageInfo {
+    let Some(exif) = exif else {
+        return ImageInfo {
+            dimensions,
+            ..ImageInfo::default()
+        };
+    };
+
+    let ascii = |tag: Tag| match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(values) => values
+            .first()
+            .map(|value| {
+                String::from_utf8_lossy(value)
+                    .trim_matches(['\0', ' '])
+                    .to_string()
+            })
+            .filter(|value| !value.is_empty()),
+        _ => None,
+    };
+
+    // orientations 5 to 8 turn the image by a quarter
+    let rotated = exif
+        .get_field(Tag::Orientation, In::PRIMARY)
+        .and_then(|field| field.value.get_uint(0))
+        .is_some_and(|orientation| (5..=8).contains(&orientation));
+
+    // `2024:05:01 12:30:45`, with dashes in the date
+    let captured = ascii(Tag::DateTimeOriginal)
+        .or_else(|| ascii(Tag::DateTime))
+        .filter(|date| date.is_ascii() && date.len() >= 19 && !date.starts_with("0000"))
+        .map(|date| date[..19].replacen(':', "-", 2));
+
+    let camera = match (ascii(Tag::Make), ascii(Tag::Model)) {
+        (Some(make), Some(model)) if model.starts_with(&make) => Some(model),
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.or(model),
+    };
+
+    ImageInfo {
+        dimensions: dimensions.map(|(width, height)| {
+            if rotated {
+                (height, width)
+            } else {
+                (width, height)
+            }
+        }),
+        captured,
+        camera,
+    }
+}
@@ -0,0 +1,655 @@
+use serde_derive::Serialize;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// How far into an MP3 file the first frame is looked for, after the ID3 tag
+const MP3_SYNC_LENGTH: u64 = 64 * 1024;
+
+/// MPEG-1 layer III bitrates in kb/s, by bitrate index
+const MPEG1_BITRATES: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+/// MPEG-2 and 2.5 layer III bitrates in kb/s, by bitrate index
+const MPEG2_BITRATES: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// Sample entry formats of MP4 tracks and Matroska codec IDs, and the usual name of the codec
+const CODEC_NAMES: [(&str, &str); 22] = [
+    ("avc1", "h264"),
+    ("avc3", "h264"),
+    ("hvc1", "hevc"),
+    ("hev1", "hevc"),
+    ("av01", "av1"),
+    ("vp08", "vp8"),
+    ("vp09", "vp9"),
+    ("mp4v", "mpeg4"),
+    ("apcn", "prores"),
+    ("mp4a", "aac"),
+    ("ac-3", "ac3"),
+    ("ec-3", "eac3"),
+    ("Opus", "opus"),
+    ("fLaC", "flac"),
+    ("alac", "alac"),
+    ("V_MPEG4/ISO/AVC", "h264"),
+    ("V_MPEGH/ISO/HEVC", "hevc"),
+    ("V_AV1", "av1"),
+    ("V_VP8", "vp8"),
+    ("V_VP9", "vp9"),
+    ("A_AAC", "aac"),
+    ("A_OPUS", "opus"),
+];
+
+/// What the headers of an audio or video file say about it (`--media`)
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct MediaInfo {
+    /// In seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    /// Average bits per second, for files without video
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bitrate: Option<u32>,
+    /// Width and height in pixels of the first video track
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<(u32, u32)>,
+    /// `wav`, `flac`, `mp3`, `mp4`, `mov`, `mkv` or `webm`
+    pub container: &'static str,
+    /// The codec of the first video track, or else of the first audio track, like `h264`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub codec: Option<String>,
+}
+
+/// Reads the duration, bitrate or resolution, and codec of the WAV, FLAC, MP3, MP4, MOV,
+/// Matroska or WebM file at `path`.
+///
+/// Only the headers are read, seeking over the audio and video data. `None` when it can't be
+/// read or is in another format.
+pub fn read_media_info(path: &Path) -> Option<MediaInfo> {
+    let mut file = BufReader::new(File::open(path).ok()?);
+    let length = file.get_ref().metadata().ok()?.len();
+
+    let mut header = [0; 12];
+    file.read_exact(&mut header).ok()?;
+    file.seek(SeekFrom::Start(0)).ok()?;
+
+    let mut info = if header.starts_with(b"RIFF") && &header[8..12] == b"WAVE" {
+        read_wav(&mut file)?
+    } else if header.starts_with(b"fLaC") {
+        read_flac(&mut file)?
+    } else if &header[4..8] == b"ftyp" {
+        read_mp4(&mut file, length)?
+    } else if header.starts_with(b"\x1a\x45\xdf\xa3") {
+        read_matroska(&mut file, length)?
+    } else if header.starts_with(b"ID3") || (header[0] == 0xff && header[1] & 0xe0 == 0xe0) {
+        read_mp3(&mut file, length)?
+    } else {
+        return None;
+    };
+
+    // the average bitrate of the whole file, when its headers don't give one
+    if info.bitrate.is_none() && info.resolution.is_none() {
+        info.bitrate = info
+            .duration
+            .filter(|duration| *duration > 0.0)
+            .map(|duration| (length as f64 * 8.0 / duration) as u32);
+    }
+
+    Some(info)
+}
+
+fn be32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn be64(bytes: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(bytes.get(at..at + 8)?.try_into().ok()?))
+}
+
+fn le16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn le32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Reads `length` bytes at `offset`, fewer at the end of the file
+fn read_at(file: &mut BufReader<File>, offset: u64, length: u64) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.take(length).read_to_end(&mut bytes).ok()?;
+    Some(bytes)
+}
+
+fn codec_name(codec: &str) -> String {
+    CODEC_NAMES
+        .iter()
+        .find(|(known, _)| *known == codec)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| {
+            let codec = codec.trim_start_matches("V_").trim_start_matches("A_");
+            codec.trim_matches(['\0', ' ']).to_lowercase()
+        })
+}
+
+/// The `fmt ` and `data` chunks of a RIFF WAVE file
+fn read_wav(file: &mut BufReader<File>) -> Option<MediaInfo> {
+    let mut format = None;
+    let mut data_length = None;
+    let mut offset = 12;
+
+    while format.is_none() || data_length.is_none() {
+        let header = read_at(file, offset, 8)?;
+        let length = le32(&header, 4)? as u64;
+
+        match &header[..4] {
+            b"fmt " => format = Some(read_at(file, offset + 8, 16)?),
+            b"data" => data_length = Some(length),
+            _ => {}
+        }
+        // chunks are padded to an even length
+        offset += 8 + length + length % 2;
+    }
+
+    let (format, data_length) = (format?, data_length?);
+    let byte_rate = le32(&format, 8)?;
+    let codec = match le16(&format, 0)? {
+        1 | 0xfffe => Some("pcm"),
+        3 => Some("float"),
+        6 => Some("alaw"),
+        7 => Some("ulaw"),
+        0x11 => Some("adpcm"),
+        0x55 => Some("mp3"),
+        _ => None,
+    };
+
+    Some(MediaInfo {
+        duration: (byte_rate > 0).then(|| data_length as f64 / byte_rate as f64),
+        // a corrupt byte rate can't be trusted, the average bitrate of the file is used instead
+        bitrate: byte_rate.checked_mul(8),
+        container: "wav",
+        codec: codec.map(str::to_string),
+        ..MediaInfo::default()
+    })
+}
+
+/// The `STREAMINFO` block, which always comes first in a FLAC file
+fn read_flac(file: &mut BufReader<File>) -> Option<MediaInfo> {
+    let block = read_at(file, 4, 4 + 34)?;
+    if block[0] & 0x7f != 0 {
+        return None;
+    }
+
+    // 20 bits of sample rate, 3 of channels, 5 of bits per sample and 36 of samples
+    let bits = be64(&block, 4 + 10)?;
+    let sample_rate = bits >> 44;
+    let samples = bits & 0xf_ffff_ffff;
+
+    Some(MediaInfo {
+        duration: (sample_rate > 0 && samples > 0).then(|| samples as f64 / sample_rate as f64),
+        container: "flac",
+        codec: Some("flac".to_string()),
+        ..MediaInfo::default()
+    })
+}
+
+/// The first frame of an MP3 file, after its ID3 tag, and the Xing or VBRI header of variable
+/// bitrate files in it
+fn read_mp3(file: &mut BufReader<File>, length: u64) -> Option<MediaInfo> {
+    let id3 = read_at(file, 0, 10)?;
+    let start = if id3.starts_with(b"ID3") {
+        // a "syncsafe" size, 7 bits per byte, plus a footer when the flags say so
+        let size = id3[6..10]
+            .iter()
+            .fold(0u64, |size, byte| size << 7 | (byte & 0x7f) as u64);
+        let footer = if id3[5] & 0x10 != 0 { 10 } else { 0 };
+        10 + size + footer
+    } else {
+        0
+    };
+
+    let bytes = read_at(file, start, MP3_SYNC_LENGTH)?;
+    let (offset, frame) = bytes.windows(4).enumerate().find_map(|(offset, header)| {
+        let frame = Mp3Frame::parse(header)?;
+        // a second frame right after the first one makes a false sync unlikely
+        let next = offset + frame.length;
+        match bytes.get(next..next + 4) {
+            Some(next) if Mp3Frame::parse(next).is_none() => None,
+            _ => Some((offset, frame)),
+        }
+    })?;
+
+    let audio_length = length.saturating_sub(start + offset as u64);
+    let first_frame = &bytes[offset..];
+
+    let xing = 4 + frame.side_info_length;
+    let (frames, vbr_bytes) = if matches!(first_frame.get(xing..xing + 4), Some(b"Xing" | b"Info"))
+    {
+        let flags = be32(first_frame, xing + 4)?;
+        let mut at = xing + 8;
+        let frames = (flags & 1 != 0).then(|| be32(first_frame, at)).flatten();
+        if flags & 1 != 0 {
+            at += 4;
+        }
+        let bytes = (flags & 2 != 0).then(|| be32(first_frame, at)).flatten();
+        (frames, bytes)
+    } else if first_frame.get(36..40) == Some(b"VBRI") {
+        (be32(first_frame, 36 + 14), be32(first_frame, 36 + 10))
+    } else {
+        (None, None)
+    };
+
+    let (duration, bitrate) = match frames {
+        Some(frames) => {
+            let duration =
+                frames as f64 * frame.samples_per_frame as f64 / frame.sample_rate as f64;
+            let bytes = vbr_bytes.map(u64::from).unwrap_or(audio_length);
+            let bitrate = (duration > 0.0).then(|| (bytes as f64 * 8.0 / duration) as u32);
+            (Some(duration), bitrate)
+        }
+        // constant bitrate: every frame has the bitrate of the first one
+        None => (
+            Some(audio_length as f64 * 8.0 / frame.bitrate as f64),
+            Some(frame.bitrate),
+        ),
+    };
+
+    Some(MediaInfo {
+        duration,
+        bitrate,
+        container: "mp3",
+        codec: Some("mp3".to_string()),
+        ..MediaInfo::default()
+    })
+}
+
+/// What the 4 bytes header of an MPEG audio layer III frame says
+struct Mp3Frame {
+    /// In bits per second
+    bitrate: u32,
+    sample_rate: u32,
+    samples_per_frame: u32,
+    /// Where the Xing header goes, after the frame header
+    side_info_length: usize,
+    /// In bytes, padding included
+    length: usize,
+}
+
+impl Mp3Frame {
+    fn parse(header: &[u8]) -> Option<Mp3Frame> {
+        if header[0] != 0xff || header[1] & 0xe0 != 0xe0 {
+            return None;
+        }
+
+        // 3 for MPEG-1, 2 for MPEG-2 and 0 for MPEG-2.5, and 1 for layer III
+        let version = (header[1] >> 3) & 0b11;
+        let layer = (header[1] >> 1) & 0b11;
+        if version == 1 || layer != 1 {
+            return None;
+        }
+
+        let bitrates = if version == 3 {
+            MPEG1_BITRATES
+        } else {
+            MPEG2_BITRATES
+        };
+        let bitrate = *bitrates
+            .get((header[2] >> 4) as usize)
+            .filter(|kbps| **kbps > 0)?
+            * 1000;
+        let sample_rate = [44_100, 48_000, 32_000].get(((header[2] >> 2) & 0b11) as usize)?
+            >> match version {
+                3 => 0,
+                2 => 1,
+                _ => 2,
+            };
+        let padding = ((header[2] >> 1) & 1) as usize;
+        let mono = header[3] >> 6 == 0b11;
+
+        let (samples_per_frame, side_info_length) = match (version, mono) {
+            (3, false) => (1152, 32),
+            (3, true) => (1152, 17),
+            (_, false) => (576, 17),
+            (_, true) => (576, 9),
+        };
+
+        Some(Mp3Frame {
+            bitrate,
+            sample_rate,
+            samples_per_frame,
+            side_info_length,
+            length: (samples_per_frame / 8 * bitrate / sample_rate) as usize + padding,
+        })
+    }
+}
+
+/// The boxes between `start` and `end`, as their type and the range of their content
+fn mp4_boxes(file: &mut BufReader<File>, start: u64, end: u64) -> Option<Vec<([u8; 4], u64, u64)>> {
+    let mut boxes = Vec::new();
+    let mut offset = start;
+
+    while end.saturating_sub(offset) >= 8 {
+        let header = read_at(file, offset, 16)?;
+        let kind: [u8; 4] = header.get(4..8)?.try_into().ok()?;
+        let (size, header_length) = match be32(&header, 0)? {
+            // a size of 0 means the box goes on until the end
+            0 => (end - offset, 8),
+            1 => (be64(&header, 8)?, 16),
+            size => (size as u64, 8),
+        };
+        if size < header_length || offset + header_length > end {
+            break;
+        }
+        // a crafted size could wrap around and read the same boxes forever
+        let Some(box_end) = offset.checked_add(size).filter(|box_end| *box_end > offset) else {
+            break;
+        };
+
+        boxes.push((kind, offset + header_length, box_end.min(end)));
+        offset = box_end;
+    }
+
+    Some(boxes)
+}
+
+#[derive(Debug, Default, PartialEq)]
+enum TrackKind {
+    Video,
+    Audio,
+    #[default]
+    Other,
+}
+
+/// A track of an MP4 or Matroska file
+#[derive(Debug, Default)]
+struct Track {
+    kind: TrackKind,
+    /// As written in the file, like `avc1` or `V_VP9`
+    codec: Option<String>,
+    dimensions: Option<(u32, u32)>,
+}
+
+/// The resolution of the first video track, and its codec or else the one of the first audio
+/// track
+fn describe_tracks(info: &mut MediaInfo, tracks: &[Track]) {
+    let video = tracks.iter().find(|track| track.kind == TrackKind::Video);
+    let audio = tracks.iter().find(|track| track.kind == TrackKind::Audio);
+
+    info.resolution = video.and_then(|track| track.dimensions);
+    info.codec = video
+        .or(audio)
+        .and_then(|track| track.codec.as_deref())
+        .map(codec_name);
+}
+
+/// The `moov` box of an MP4 or QuickTime file, wherever it is, and the boxes in it that
+/// describe the movie and its tracks
+fn read_mp4(file: &mut BufReader<File>, length: u64) -> Option<MediaInfo> {
+    let mut info = MediaInfo {
+        container: "mp4",
+        ..MediaInfo::default()
+    };
+    let mut tracks = Vec::new();
+
+    for (kind, start, end) in mp4_boxes(file, 0, length)? {
+        match &kind {
+            b"ftyp" if read_at(file, start, 4)? == b"qt  " => info.container = "mov",
+            b"moov" => {
+                for (kind, start, end) in mp4_boxes(file, start, end)? {
+                    match &kind {
+                        b"mvhd" => info.duration = mp4_duration(&read_at(file, start, 32)?),
+                        b"trak" => tracks.push(read_mp4_track(file, start, end)?),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    describe_tracks(&mut info, &tracks);
+
+    Some(info)
+}
+
+/// The duration in seconds of an `mvhd` box, version 0 or 1
+fn mp4_duration(mvhd: &[u8]) -> Option<f64> {
+    let (timescale, duration) = match mvhd.first()? {
+        0 => (be32(mvhd, 12)?, be32(mvhd, 16)? as u64),
+        _ => (be32(mvhd, 20)?, be64(mvhd, 24)?),
+    };
+
+    (timescale > 0).then(|| duration as f64 / timescale as f64)
+}
+
+/// A `trak` box, with the dimensions in its `tkhd`, the kind in `mdia/hdlr` and the codec in
+/// `mdia/minf`
+fn read_mp4_track(file: &mut BufReader<File>, start: u64, end: u64) -> Option<Track> {
+    let mut track = Track::default();
+
+    for (kind, start, end) in mp4_boxes(file, start, end)? {
+        match &kind {
+            b"tkhd" => {
+                // width and height are 16.16 fixed point numbers at the end of the box
+                let tkhd = read_at(file, start, 92)?;
+                let at = if tkhd.first()? == &0 { 76 } else { 88 };
+                track.dimensions = Some((be32(&tkhd, at)? >> 16, be32(&tkhd, at + 4)? >> 16))
+                    .filter(|(width, height)| *width > 0 && *height > 0);
+            }
+            b"mdia" => {
+                for (kind, start, end) in mp4_boxes(file, start, end)? {
+                    match &kind {
+                        b"hdlr" => {
+                            track.kind = match read_at(file, start + 8, 4)?.as_slice() {
+                                b"vide" => TrackKind::Video,
+                                b"soun" => TrackKind::Audio,
+                                _ => TrackKind::Other,
+                            }
+                        }
+                        b"minf" => track.codec = mp4_sample_format(file, start, end),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(track)
+}
+
+/// The format of the first sample entry in `minf/stbl/stsd`, like `avc1` or `mp4a`
+fn mp4_sample_format(file: &mut BufReader<File>, start: u64, end: u64) -> Option<String> {
+    let (_, start, end) = mp4_boxes(file, start, end)?
+        .into_iter()
+        .find(|(kind, _, _)| kind == b"stbl")?;
+    let (_, start, _) = mp4_boxes(file, start, end)?
+        .into_iter()
+        .find(|(kind, _, _)| kind == b"stsd")?;
+
+    // a version and flags, the number of entries, then the size and format of the first one
+    let stsd = read_at(file, start, 16)?;
+    Some(String::from_utf8_lossy(stsd.get(12..16)?).to_string())
+}
+
+/// Reads the ID and size of the EBML element at the position of `file`, the size being `None`
+/// when unknown
+fn ebml_element(file: &mut BufReader<File>) -> Option<(u32, Option<u64>, u64)> {
+    let (id, id_length) = ebml_vint(file, true)?;
+    let (size, size_length) = ebml_vint(file, false)?;
+    let unknown = size == (1 << (7 * size_length)) - 1;
+
+    Some((
+        id as u32,
+        (!unknown).then_some(size),
+        (id_length + size_length) as u64,
+    ))
+}
+
+/// A variable length integer, with its length marker kept for IDs
+fn ebml_vint(file: &mut BufReader<File>, keep_marker: bool) -> Option<(u64, u32)> {
+    let mut first = [0];
+    file.read_exact(&mut first).ok()?;
+    let length = first[0].leading_zeros() + 1;
+    if length > 8 {
+        return None;
+    }
+
+    let mut value = if keep_marker {
+        first[0] as u64
+    } else {
+        first[0] as u64 & (0xff >> length)
+    };
+    for _ in 1..length {
+        let mut byte = [0];
+        file.read_exact(&mut byte).ok()?;
+        value = value << 8 | byte[0] as u64;
+    }
+
+    Some((value, length))
+}
+
+/// The children of the EBML element whose content goes from `start` to `end`, as their ID
+/// and the range of their content
+fn ebml_children(file: &mut BufReader<File>, start: u64, end: u64) -> Option<Vec<(u32, u64, u64)>> {
+    let mut children = Vec::new();
+    let mut offset = start;
+    file.seek(SeekFrom::Start(start)).ok()?;
+
+    while offset < end {
+        let Some((id, size, header_length)) = ebml_element(file) else {
+            break;
+        };
+        // a header going past the end of the parent is malformed
+        let content_start = offset + header_length;
+        if content_start > end {
+            break;
+        }
+        let content_end = size.map_or(end, |size| content_start.saturating_add(size).min(end));
+        children.push((id, content_start, content_end));
+
+        // the clusters with the frames come after the information lse needs
+        if id == MATROSKA_CLUSTER || size.is_none() {
+            break;
+        }
+        offset = content_end;
+        file.seek(SeekFrom::Start(offset)).ok()?;
+    }
+
+    Some(children)
+}
+
+const EBML_DOC_TYPE: u32 = 0x4282;
+const MATROSKA_SEGMENT: u32 = 0x1853_8067;
+const MATROSKA_INFO: u32 = 0x1549_a966;
+const MATROSKA_TIMESTAMP_SCALE: u32 = 0x2a_d7b1;
+const MATROSKA_DURATION: u32 = 0x4489;
+const MATROSKA_TRACKS: u32 = 0x1654_ae6b;
+const MATROSKA_TRACK_ENTRY: u32 = 0xae;
+const MATROSKA_TRACK_TYPE: u32 = 0x83;
+const MATROSKA_CODEC_ID: u32 = 0x86;
+const MATROSKA_VIDEO: u32 = 0xe0;
+const MATROSKA_PIXEL_WIDTH: u32 = 0xb0;
+const MATROSKA_PIXEL_HEIGHT: u32 = 0xba;
+const MATROSKA_CLUSTER: u32 = 0x1f43_b675;
+
+fn ebml_uint(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |value, byte| value << 8 | *byte as u64)
+}
+
+/// The EBML header for the document type, then the `Info` and `Tracks` of the segment
+fn read_matroska(file: &mut BufReader<File>, length: u64) -> Option<MediaInfo> {
+    let mut info = MediaInfo {
+        container: "mkv",
+        ..MediaInfo::default()
+    };
+    let mut scale = 1_000_000;
+    let mut duration = None;
+    let mut tracks = Vec::new();
+
+    for (id, start, end) in ebml_children(file, 0, length)? {
+        if id == 0x1a45_dfa3 {
+            for (id, start, end) in ebml_children(file, start, end)? {
+                if id == EBML_DOC_TYPE && read_at(file, start, (end - start).min(16))? == b"webm" {
+                    info.container = "webm";
+                }
+            }
+        }
+        if id != MATROSKA_SEGMENT {
+            continue;
+        }
+
+        for (id, start, end) in ebml_children(file, start, end)? {
+            match id {
+                MATROSKA_INFO => {
+                    for (id, start, end) in ebml_children(file, start, end)? {
+                        let value = read_at(file, start, (end - start).min(8))?;
+                        match id {
+                            MATROSKA_TIMESTAMP_SCALE => scale = ebml_uint(&value),
+                            MATROSKA_DURATION => {
+                                duration = match value.len() {
+                                    4 => Some(f32::from_be_bytes(value.try_into().ok()?) as f64),
+                                    8 => Some(f64::from_be_bytes(value.try_into().ok()?)),
+                                    _ => None,
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                MATROSKA_TRACKS => {
+                    for (id, start, end) in ebml_children(file, start, end)? {
+                        if id == MATROSKA_TRACK_ENTRY {
+                            tracks.push(read_matroska_track(file, start, end)?);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // the duration is in ticks of `scale` nanoseconds
+    info.duration = duration.map(|duration| duration * scale as f64 / 1e9);
+    describe_tracks(&mut info, &tracks);
+
+    Some(info)
+}
+
+/// A `TrackEntry`, with its type, codec ID and the pixel size in its `Video` element
+fn read_matroska_track(file: &mut BufReader<File>, start: u64, end: u64) -> Option<Track> {
+    let mut track = Track::default();
+    let mut width = None;
+    let mut height = None;
+
+    for (id, start, end) in ebml_children(file, start, end)? {
+        match id {
+            MATROSKA_TRACK_TYPE => {
+                track.kind = match ebml_uint(&read_at(file, start, (end - start).min(8))?) {
+                    1 => TrackKind::Video,
+                    2 => TrackKind::Audio,
+                    _ => TrackKind::Other,
+                }
+            }
+            MATROSKA_CODEC_ID => {
+                let codec = read_at(file, start, (end - start).min(64))?;
+                track.codec = Some(String::from_utf8_lossy(&codec).to_string());
+            }
+            MATROSKA_VIDEO => {
+                for (id, start, end) in ebml_children(file, start, end)? {
+                    let value = ebml_uint(&read_at(file, start, (end - start).min(8))?) as u32;
+                    match id {
+                        MATROSKA_PIXEL_WIDTH => width = Some(value),
+                        MATROSKA_PIXEL_HEIGHT => height = Some(value),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    track.dimensions = width.zip(height);
+    Some(track)
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::types::files::{FileType, EXTENSION_TYPES};
+
+/// The file type and MIME type of each extension of `EXTENSION_TYPES`, built once
+fn extensions() -> &'static HashMap<&'static str, (FileType, &'static str)> {
+    static EXTENSIONS: OnceLock<HashMap<&str, (FileType, &str)>> = OnceLock::new();
+
+    EXTENSIONS.get_or_init(|| {
+        EXTENSION_TYPES
+            .iter()
+            .map(|(extension, file_type, mime)| (*extension, (*file_type, *mime)))
+            .collect()
+    })
+}
+
+/// The MIME type of the first extension of each file type, built once
+fn file_type_mimes() -> &'static HashMap<FileType, &'static str> {
+    static FILE_TYPE_MIMES: OnceLock<HashMap<FileType, &str>> = OnceLock::new();
+
+    FILE_TYPE_MIMES.get_or_init(|| {
+        let mut mimes = HashMap::new();
+        for (_, file_type, mime) in EXTENSION_TYPES {
+            mimes.entry(file_type).or_insert(mime);
+        }
+        mimes
+    })
+}
+
+/// The MIME type of an extension of `EXTENSION_TYPES`
+pub fn extension_mime(extension: &str) -> Option<&'static str> {
+    extensions().get(extension).map(|(_, mime)| *mime)
+}
+
+/// The MIME type of a file type, when nothing more precise is known
+pub fn file_type_mime(file_type: FileType) -> &'static str {
+    match file_type {
+        FileType::Git | FileType::License | FileType::Config => "text/plain",
+        FileType::Binary | FileType::Unknown | FileType::None => "application/octet-stream",
+        _ => file_type_mimes()
+            .get(&file_type)
+            .copied()
+            .unwrap_or("application/octet-stream"),
+    }
+}
+
+/// The MIME type of a file named `name` with the file type `file_type`.
+///
+/// The MIME type of the extension is only used when the extension alone gives that file type,
+/// so a rule making `*.test.ts` Markdown makes it `text/markdown` too, and the icon and MIME
+/// type never disagree.
+pub fn mime_of(name: &str, file_type: FileType) -> &'static str {
+    let from_extension = Path::new(name)
+        .extension()
+        .and_then(|extension| {
+            // like file types, `photo.JPG` is looked up as it is, then in lowercase
+            let extension = extension.to_string_lossy();
+            extensions()
+                .get(extension.as_ref())
+                .or_else(|| extensions().get(extension.to_lowercase().as_str()))
+        })
+        .filter(|(of_extension, _)| *of_extension == file_type)
+        .map(|(_, mime)| *mime);
+
+    from_extension.unwrap_or(file_type_mime(file_type))
+}
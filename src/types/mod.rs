@@ -1 +1,9 @@
+pub mod archive;
+pub mod entry;
 pub mod files;
+pub mod icons;
+pub mod image;
+pub mod media;
+pub mod mime;
+pub mod rules;
+pub mod sniff;
@@ -0,0 +1,256 @@
+use globset::{Glob, GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::types::{
+    files::{
+        FileType, COMPOUND_EXTENSION_TYPES, EXTENSION_TYPES, GLOB_TYPES, LOWERCASE_NAME_TYPES,
+        NAME_TYPES,
+    },
+    sniff::sniff_file,
+};
+
+/// What a rule looks at in a file name
+#[derive(Debug, Clone)]
+pub enum Matcher {
+    /// The whole name
+    Name(String),
+    /// A glob like `*.test.ts` or `Dockerfile.*`
+    Glob(GlobMatcher),
+    /// The end of the name after a dot, which can have several parts like `tar.gz`
+    Extension(String),
+}
+
+/// A rule of the config, giving a file type to the names it matches.
+///
+/// ```toml
+/// [[rules]]
+/// glob = "*.test.ts"
+/// type = "typescript"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawRule", into = "RawRule")]
+pub struct TypeRule {
+    pub matcher: Matcher,
+    pub ignore_case: bool,
+    pub file_type: FileType,
+}
+
+/// A rule as written in TOML, with exactly one of `name`, `glob` and `extension`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    glob: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    extension: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    ignore_case: bool,
+    #[serde(rename = "type")]
+    file_type: FileType,
+}
+
+fn compile_glob(glob: &str, ignore_case: bool) -> Result<GlobMatcher, String> {
+    GlobBuilder::new(glob)
+        .case_insensitive(ignore_case)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| e.to_string())
+}
+
+impl TryFrom<RawRule> for TypeRule {
+    type Error = String;
+
+    fn try_from(raw: RawRule) -> Result<Self, Self::Error> {
+        let matcher = match (raw.name, raw.glob, raw.extension) {
+            (Some(name), None, None) => Matcher::Name(name),
+            (None, Some(glob), None) => Matcher::Glob(compile_glob(&glob, raw.ignore_case)?),
+            (None, None, Some(extension)) => {
+                Matcher::Extension(extension.trim_start_matches('.').to_string())
+            }
+            _ => {
+                return Err("a rule needs exactly one of `name`, `glob` and `extension`".to_string())
+            }
+        };
+
+        Ok(TypeRule {
+            matcher,
+            ignore_case: raw.ignore_case,
+            file_type: raw.file_type,
+        })
+    }
+}
+
+impl From<TypeRule> for RawRule {
+    fn from(rule: TypeRule) -> Self {
+        let mut raw = RawRule {
+            name: None,
+            glob: None,
+            extension: None,
+            ignore_case: rule.ignore_case,
+            file_type: rule.file_type,
+        };
+
+        match rule.matcher {
+            Matcher::Name(name) => raw.name = Some(name),
+            Matcher::Glob(glob) => raw.glob = Some(glob.glob().to_string()),
+            Matcher::Extension(extension) => raw.extension = Some(extension),
+        }
+
+        raw
+    }
+}
+
+fn ends_with_extension(name: &str, extension: &str) -> bool {
+    name.len() > extension.len() + 1
+        && name.ends_with(extension)
+        && name[..name.len() - extension.len()].ends_with('.')
+}
+
+impl TypeRule {
+    pub fn matches(&self, name: &str) -> bool {
+        let equal = |a: &str, b: &str| {
+            if self.ignore_case {
+                a.eq_ignore_ascii_case(b)
+            } else {
+                a == b
+            }
+        };
+
+        match &self.matcher {
+            Matcher::Name(expected) => equal(name, expected),
+            Matcher::Glob(glob) => glob.is_match(name),
+            Matcher::Extension(extension) if self.ignore_case => {
+                ends_with_extension(&name.to_lowercase(), &extension.to_lowercase())
+            }
+            Matcher::Extension(extension) => ends_with_extension(name, extension),
+        }
+    }
+}
+
+/// Finds the file type of a name, trying in order:
+///
+/// 1. the rules of the config, in the order they are written
+/// 2. exact names, like `Cargo.toml`
+/// 3. names whatever their case, like `Makefile` or `README`
+/// 4. globs, like `Dockerfile.*`
+/// 5. extensions made of several parts, like `tar.gz`
+/// 6. extensions, whatever their case when they aren't known as they are
+///
+/// and, with `set_sniff`, from the first bytes of the files none of them match
+#[derive(Debug, Clone)]
+pub struct FileTypeRules {
+    configured: Vec<TypeRule>,
+    names: HashMap<&'static str, FileType>,
+    lowercase_names: HashMap<&'static str, FileType>,
+    globs: GlobSet,
+    glob_types: Vec<FileType>,
+    compound_extensions: Vec<(&'static str, FileType)>,
+    extensions: HashMap<&'static str, FileType>,
+    sniff: bool,
+}
+
+impl FileTypeRules {
+    /// The built-in rules, preceded by the ones of the config
+    pub fn new(configured: &[TypeRule]) -> FileTypeRules {
+        let mut builder = GlobSetBuilder::new();
+        for (glob, _) in GLOB_TYPES {
+            builder.add(Glob::new(glob).expect("invalid built-in glob"));
+        }
+
+        let mut compound_extensions = COMPOUND_EXTENSION_TYPES.to_vec();
+        compound_extensions.sort_by_key(|(extension, _)| std::cmp::Reverse(extension.len()));
+
+        FileTypeRules {
+            configured: configured.to_vec(),
+            names: NAME_TYPES.into_iter().collect(),
+            lowercase_names: LOWERCASE_NAME_TYPES.into_iter().collect(),
+            globs: builder.build().expect("invalid built-in globs"),
+            glob_types: GLOB_TYPES.iter().map(|(_, file_type)| *file_type).collect(),
+            compound_extensions,
+            extensions: EXTENSION_TYPES
+                .iter()
+                .map(|(extension, file_type, _)| (*extension, *file_type))
+                .collect(),
+            sniff: false,
+        }
+    }
+
+    /// Read the first bytes of files without extension to guess their type
+    pub fn set_sniff(mut self, sniff: bool) -> Self {
+        self.sniff = sniff;
+        self
+    }
+
+    /// The built-in rules alone, built once
+    pub fn builtin() -> &'static FileTypeRules {
+        static BUILTIN: OnceLock<FileTypeRules> = OnceLock::new();
+        BUILTIN.get_or_init(|| FileTypeRules::new(&[]))
+    }
+
+    /// The file type of a name matched by a rule, `None` when it would only be guessed
+    pub fn known(&self, file: &str) -> Option<FileType> {
+        let path = Path::new(file);
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+
+        if let Some(rule) = self.configured.iter().find(|rule| rule.matches(&name)) {
+            return Some(rule.file_type);
+        }
+
+        if let Some(file_type) = self.names.get(name.as_ref()) {
+            return Some(*file_type);
+        }
+
+        if let Some(file_type) = self.lowercase_names.get(name.to_lowercase().as_str()) {
+            return Some(*file_type);
+        }
+
+        if let Some(index) = self.globs.matches(name.as_ref()).into_iter().min() {
+            return Some(self.glob_types[index]);
+        }
+
+        if let Some((_, file_type)) = self
+            .compound_extensions
+            .iter()
+            .find(|(extension, _)| ends_with_extension(&name, extension))
+        {
+            return Some(*file_type);
+        }
+
+        // `photo.JPG` is a photo too, but `.S` and `.s` are both kept for assembly
+        let extension = path.extension()?.to_string_lossy();
+        self.extensions
+            .get(extension.as_ref())
+            .or_else(|| self.extensions.get(extension.to_lowercase().as_str()))
+            .copied()
+    }
+
+    /// The file type of a name: the one of its rule, `Unknown` for unknown extensions and
+    /// `None` without any extension
+    pub fn file_type(&self, file: &str) -> FileType {
+        match self.known(file) {
+            Some(file_type) => file_type,
+            None if Path::new(file).extension().is_some() => FileType::Unknown,
+            None => FileType::None,
+        }
+    }
+
+    /// The file type of the file at `path` named `file`, sniffed when its name says nothing,
+    /// with the MIME type sniffing found
+    pub fn file_type_at(&self, file: &str, path: &Path) -> (FileType, Option<&'static str>) {
+        match self.file_type(file) {
+            FileType::None if self.sniff => match sniff_file(path) {
+                Some((file_type, mime)) => (file_type, Some(mime)),
+                None => (FileType::None, None),
+            },
+            file_type => (file_type, None),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::types::files::FileType;
+
+/// How many bytes at the start of a file are read to guess its type
+pub const SNIFF_LENGTH: usize = 512;
+
+/// Magic numbers at the start of a file, and the file type and MIME type they give
+const MAGIC_TYPES: [(&[u8], FileType, &str); 19] = [
+    (b"\x7fELF", FileType::Binary, "application/x-executable"),
+    (
+        b"\xfe\xed\xfa\xce",
+        FileType::Binary,
+        "application/x-mach-binary",
+    ),
+    (
+        b"\xfe\xed\xfa\xcf",
+        FileType::Binary,
+        "application/x-mach-binary",
+    ),
+    (
+        b"\xce\xfa\xed\xfe",
+        FileType::Binary,
+        "application/x-mach-binary",
+    ),
+    (
+        b"\xcf\xfa\xed\xfe",
+        FileType::Binary,
+        "application/x-mach-binary",
+    ),
+    (b"\x89PNG\r\n\x1a\n", FileType::Photo, "image/png"),
+    (b"\xff\xd8\xff", FileType::Photo, "image/jpeg"),
+    (b"GIF87a", FileType::Photo, "image/gif"),
+    (b"GIF89a", FileType::Photo, "image/gif"),
+    (b"II*\x00", FileType::Photo, "image/tiff"),
+    (b"MM\x00*", FileType::Photo, "image/tiff"),
+    (b"%PDF-", FileType::Pdf, "application/pdf"),
+    (b"PK\x03\x04", FileType::Archive, "application/zip"),
+    (b"\x1f\x8b", FileType::Archive, "application/gzip"),
+    (b"\xfd7zXZ\x00", FileType::Archive, "application/x-xz"),
+    (b"BZh", FileType::Archive, "application/x-bzip2"),
+    (b"\x28\xb5\x2f\xfd", FileType::Archive, "application/zstd"),
+    (
+        b"7z\xbc\xaf\x27\x1c",
+        FileType::Archive,
+        "application/x-7z-compressed",
+    ),
+    (b"Rar!\x1a\x07", FileType::Archive, "application/vnd.rar"),
+];
+
+/// Interpreters of shebangs without their version, with their file type and MIME type
+const INTERPRETER_TYPES: [(&str, FileType, &str); 14] = [
+    ("sh", FileType::Shell, "text/x-shellscript"),
+    ("bash", FileType::Shell, "text/x-shellscript"),
+    ("zsh", FileType::Shell, "text/x-shellscript"),
+    ("dash", FileType::Shell, "text/x-shellscript"),
+    ("ksh", FileType::Shell, "text/x-shellscript"),
+    ("fish", FileType::Shell, "text/x-shellscript"),
+    ("python", FileType::Python, "text/x-python"),
+    ("node", FileType::JavaScript, "text/javascript"),
+    ("deno", FileType::JavaScript, "text/javascript"),
+    ("bun", FileType::JavaScript, "text/javascript"),
+    ("ts-node", FileType::TypeScript, "text/x-typescript"),
+    ("lua", FileType::Lua, "text/x-lua"),
+    ("php", FileType::Php, "application/x-httpd-php"),
+    ("make", FileType::Make, "text/x-makefile"),
+];
+
+/// The file type and MIME type of the file at `path` guessed from its first bytes, `None` when
+/// it's empty or can't be read
+pub fn sniff_file(path: &Path) -> Option<(FileType, &'static str)> {
+    let mut bytes = Vec::with_capacity(SNIFF_LENGTH);
+    File::open(path)
+        .ok()?
+        .take(SNIFF_LENGTH as u64)
+        .read_to_end(&mut bytes)
+        .ok()?;
+
+    sniff(&bytes)
+}
+
+/// The file type and MIME type given by the first bytes of a file: a shebang, a magic number,
+/// or else whether it looks like text
+pub fn sniff(bytes: &[u8]) -> Option<(FileType, &'static str)> {
+    if bytes.is_empty() {
+        return None;
+    }
+
+    if let Some(line) = bytes.strip_prefix(b"#!") {
+        let line = line.split(|byte| *byte == b'\n').next().unwrap_or_default();
+        return Some(
+            shebang_type(&String::from_utf8_lossy(line)).unwrap_or((FileType::Text, "text/plain")),
+        );
+    }
+
+    // universal Mach-O binaries and Java classes both start with `cafebabe`, followed by the
+    // number of architectures of the binary, or the version of the class (45 and up)
+    if let Some(next) = bytes
+        .strip_prefix(b"\xca\xfe\xba\xbe")
+        .and_then(|rest| rest.get(..4))
+    {
+        return Some(match u32::from_be_bytes(next.try_into().ok()?) {
+            0..20 => (FileType::Binary, "application/x-mach-binary"),
+            _ => (FileType::Java, "application/java-vm"),
+        });
+    }
+
+    if let Some((_, file_type, mime)) = MAGIC_TYPES
+        .iter()
+        .find(|(magic, _, _)| bytes.starts_with(magic))
+    {
+        return Some((*file_type, mime));
+    }
+
+    if bytes.get(257..262) == Some(b"ustar") {
+        return Some((FileType::Archive, "application/x-tar"));
+    }
+
+    if is_text(bytes) {
+        Some((FileType::Text, "text/plain"))
+    } else {
+        Some((FileType::Binary, "application/octet-stream"))
+    }
+}
+
+/// The file type and MIME type of a shebang line like `/usr/bin/env python3`, without the `#!`
+fn shebang_type(line: &str) -> Option<(FileType, &'static str)> {
+    let mut words = line.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+
+    let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+    INTERPRETER_TYPES
+        .iter()
+        .find(|(interpreter, _, _)| *interpreter == program)
+        .map(|(_, file_type, mime)| (*file_type, *mime))
+}
+
+/// UTF-8 without NUL bytes, ignoring a character cut at the end of the bytes read
+fn is_text(bytes: &[u8]) -> bool {
+    if bytes.contains(&0) {
+        return false;
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    }
+}
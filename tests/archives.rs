@@ -0,0 +1,262 @@
+mod common;
+
+use common::scratch;
+use flate2::{write::GzEncoder, Compression};
+use ls_enhanced::{
+    config::create_config,
+    types::{
+        archive::{read_archive, ArchiveFormat},
+        entry::{Entry, EntryKind},
+        rules::FileTypeRules,
+    },
+    Errors, FileType, Lister, View,
+};
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
+use zip::{write::SimpleFileOptions, DateTime, ZipWriter};
+
+/// `project/` with `main.rs`, `logo.png` in `assets/` (without an entry of its own) and a
+/// symlink to `main.rs`
+fn tar_bytes() -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Directory);
+    header.set_mode(0o755);
+    header.set_size(0);
+    header.set_mtime(1_700_000_000);
+    builder
+        .append_data(&mut header, "project/", &[][..])
+        .unwrap();
+
+    let mut header = tar::Header::new_gnu();
+    header.set_mode(0o755);
+    header.set_size(12);
+    header.set_mtime(1_700_000_100);
+    builder
+        .append_data(&mut header, "project/main.rs", &b"fn main() {}"[..])
+        .unwrap();
+
+    let mut header = tar::Header::new_gnu();
+    header.set_mode(0o644);
+    header.set_size(4);
+    builder
+        .append_data(&mut header, "project/assets/logo.png", &b"\x89PNG"[..])
+        .unwrap();
+
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Symlink);
+    header.set_mode(0o777);
+    header.set_size(0);
+    builder
+        .append_link(&mut header, "project/run", "main.rs")
+        .unwrap();
+
+    builder.into_inner().unwrap()
+}
+
+fn child<'a>(entries: &'a [Entry], name: &str) -> &'a Entry {
+    entries
+        .iter()
+        .find(|entry| entry.name == name)
+        .unwrap_or_else(|| panic!("no {}", name))
+}
+
+fn check_project(entries: &[Entry]) {
+    assert_eq!(entries.len(), 1);
+    let project = child(entries, "project");
+    assert_eq!(project.kind, EntryKind::Directory);
+
+    let main = child(&project.children, "main.rs");
+    assert_eq!(main.kind, EntryKind::File);
+    assert_eq!(main.file_type, FileType::Rust);
+    assert_eq!(main.stat.size, 12);
+    assert!(main.is_executable());
+
+    let assets = child(&project.children, "assets");
+    assert_eq!(assets.kind, EntryKind::Directory);
+    assert_eq!(
+        child(&assets.children, "logo.png").file_type,
+        FileType::Photo
+    );
+}
+
+#[test]
+fn tar_entries_are_read_as_a_tree() {
+    let directory = scratch("tar");
+    let path = directory.join("project.tar");
+    fs::write(&path, tar_bytes()).unwrap();
+
+    let entries = read_archive(&path, ArchiveFormat::Tar, FileTypeRules::builtin()).unwrap();
+    check_project(&entries);
+
+    let project = child(&entries, "project");
+    assert_eq!(project.path, path.join("project"));
+    assert_eq!(project.stat.mode, 0o040755);
+    assert_eq!(
+        child(&project.children, "main.rs").stat.modified,
+        Some(UNIX_EPOCH + Duration::from_secs(1_700_000_100))
+    );
+
+    let run = child(&project.children, "run");
+    assert_eq!(run.kind, EntryKind::Symlink);
+    assert_eq!(run.symlink_target, Some(PathBuf::from("main.rs")));
+}
+
+#[test]
+fn compressed_tars_are_read() {
+    let directory = scratch("compressed");
+
+    let mut gz = GzEncoder::new(Vec::new(), Compression::fast());
+    gz.write_all(&tar_bytes()).unwrap();
+    let path = directory.join("project.tar.gz");
+    fs::write(&path, gz.finish().unwrap()).unwrap();
+    check_project(&read_archive(&path, ArchiveFormat::TarGz, FileTypeRules::builtin()).unwrap());
+
+    let zst = ruzstd::encoding::compress_to_vec(
+        &tar_bytes()[..],
+        ruzstd::encoding::CompressionLevel::Fastest,
+    );
+    let path = directory.join("project.tar.zst");
+    fs::write(&path, zst).unwrap();
+    check_project(&read_archive(&path, ArchiveFormat::TarZst, FileTypeRules::builtin()).unwrap());
+}
+
+#[test]
+fn zip_entries_are_read_as_a_tree() {
+    let directory = scratch("zip");
+    let path = directory.join("project.zip");
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    zip.start_file("project/main.rs", options.unix_permissions(0o755))
+        .unwrap();
+    zip.write_all(b"fn main() {}").unwrap();
+    zip.start_file("project/assets/logo.png", options).unwrap();
+    zip.write_all(b"\x89PNG").unwrap();
+    zip.add_symlink("project/run", "main.rs", options).unwrap();
+    fs::write(&path, zip.finish().unwrap().into_inner()).unwrap();
+
+    let entries = read_archive(&path, ArchiveFormat::Zip, FileTypeRules::builtin()).unwrap();
+    check_project(&entries);
+
+    let run = child(&child(&entries, "project").children, "run");
+    assert_eq!(run.kind, EntryKind::Symlink);
+    assert_eq!(run.symlink_target, Some(PathBuf::from("main.rs")));
+}
+
+#[test]
+fn archives_are_listed_like_directories() {
+    let directory = scratch("listed");
+    let path = directory.join("project.tar");
+    fs::write(&path, tar_bytes()).unwrap();
+
+    let mut out = Vec::new();
+    let mut errors = Errors::quiet();
+    Lister::new(create_config())
+        .add_path(&path)
+        .set_view(View::Json)
+        .write_to(&mut out, &mut errors)
+        .unwrap();
+
+    // not recursive: only the top of the archive
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.lines().count(), 1);
+    assert!(out.contains(r#""name":"project","#));
+    assert!(errors.messages().is_empty());
+
+    fs::write(&path, "not a tar").unwrap();
+    Lister::new(create_config())
+        .add_path(&path)
+        .set_view(View::Json)
+        .write_to(&mut Vec::new(), &mut errors)
+        .unwrap();
+    assert_eq!(errors.exit_code(), 2);
+}
+
+#[test]
+fn archived_entries_are_not_linked() {
+    let directory = scratch("links");
+    let path = directory.join("project.tar");
+    fs::write(&path, tar_bytes()).unwrap();
+
+    let entries = read_archive(&path, ArchiveFormat::Tar, FileTypeRules::builtin()).unwrap();
+    let project = child(&entries, "project");
+    assert!(project.archived);
+    assert!(child(&project.children, "assets").archived);
+
+    let mut config = create_config();
+    config.format.hyperlink = true;
+    let mut out = Vec::new();
+    Lister::new(config)
+        .add_path(&path)
+        .set_view(View::Long)
+        .write_to(&mut out, &mut Errors::quiet())
+        .unwrap();
+
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("project"));
+    assert!(!out.contains("\x1b]8;;"));
+}
+
+/// The lines of `lse -l` for an archive, recursively with `View::Tree`
+fn long_lines(path: &std::path::Path, view: View) -> Vec<String> {
+    let mut out = Vec::new();
+    Lister::new(create_config())
+        .add_path(path)
+        .set_view(view)
+        .set_show_permissions(true)
+        .write_to(&mut out, &mut Errors::quiet())
+        .unwrap();
+
+    let out = String::from_utf8(out).unwrap();
+    out.lines().map(str::to_string).collect()
+}
+
+fn line_of<'a>(lines: &'a [String], name: &str) -> &'a str {
+    lines
+        .iter()
+        .find(|line| line.ends_with(name))
+        .unwrap_or_else(|| panic!("no {} in {:?}", name, lines))
+}
+
+#[test]
+fn archived_entries_have_their_size_and_time() {
+    let directory = scratch("long");
+    let path = directory.join("project.tar");
+    fs::write(&path, tar_bytes()).unwrap();
+
+    let lines = long_lines(&path, View::Long);
+    assert!(line_of(&lines, "project").contains(" 0 2023-11-14 22:13 "));
+
+    // sizes are aligned to the right
+    let lines = long_lines(&path, View::Tree);
+    assert!(line_of(&lines, "main.rs").contains("rwxr-xr-x 12 2023-11-14 22:15 "));
+    assert!(line_of(&lines, "assets").contains("rwxr-xr-x  0 "));
+    assert!(line_of(&lines, "logo.png").contains("r--r-- 4 1970-01-01 00:00 "));
+
+    let path = directory.join("project.zip");
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let time = DateTime::from_date_and_time(2024, 5, 6, 7, 8, 10).unwrap();
+    zip.start_file(
+        "main.rs",
+        SimpleFileOptions::default().last_modified_time(time),
+    )
+    .unwrap();
+    zip.write_all(b"fn main() {}").unwrap();
+    fs::write(&path, zip.finish().unwrap().into_inner()).unwrap();
+
+    let lines = long_lines(&path, View::Long);
+    assert!(line_of(&lines, "main.rs").contains(" 12 2024-05-06 07:08 "));
+}
+
+#[test]
+fn archive_formats_come_from_the_name() {
+    assert_eq!(ArchiveFormat::of("a.tar"), Some(ArchiveFormat::Tar));
+    assert_eq!(ArchiveFormat::of("a.TGZ"), Some(ArchiveFormat::TarGz));
+    assert_eq!(ArchiveFormat::of("a.tar.zst"), Some(ArchiveFormat::TarZst));
+    assert_eq!(ArchiveFormat::of("a.zip"), Some(ArchiveFormat::Zip));
+    assert_eq!(ArchiveFormat::of("a.gz"), None);
+}
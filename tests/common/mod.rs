@@ -0,0 +1,10 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// A directory of its own for each test, emptied first
+pub fn scratch(test: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("lse-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
@@ -0,0 +1,82 @@
+use ls_enhanced::{
+    config::{create_config, Color, DirectoryStyle},
+    ls_colors::{parse_sgr, LsColors, LsColorsMode},
+    theme::{load_theme_file, Element, Theme, ThemeFile},
+    types::icons::IconSet,
+};
+use std::ffi::OsStr;
+
+fn style(theme: &Theme, name: &str) -> (String, Color) {
+    let (icon, color) = theme.directory_style(OsStr::new(name));
+    (icon.to_string(), color)
+}
+
+#[test]
+fn named_directories_have_an_icon_and_a_color() {
+    let mut config = create_config();
+    config.format.icon_set = Some(IconSet::Emoji);
+    let theme = Theme::new(&config);
+
+    assert_eq!(
+        style(&theme, ".git"),
+        ("🌱".to_string(), Color::new(241, 78, 50).set_bold(true))
+    );
+    assert_eq!(style(&theme, "demo.egg-info").0, "📦");
+    assert_eq!(style(&theme, "plain").1, theme.element(Element::Directory));
+
+    // without icons, they keep their color
+    config.format.icons = false;
+    let theme = Theme::new(&config);
+    assert_eq!(
+        style(&theme, "node_modules").1,
+        Color::new(139, 195, 74).set_bold(true)
+    );
+}
+
+#[test]
+fn configured_directories_win_over_named_ones() {
+    let mut config = create_config();
+    config.format.icon_set = Some(IconSet::Emoji);
+    let red = Color::new(255, 0, 0);
+    config.directories.insert(
+        "src".to_string(),
+        DirectoryStyle {
+            icon: None,
+            color: Some(red),
+        },
+    );
+    // not a valid glob: taken as a plain name, and the styles after it stay in place
+    config.directories.insert(
+        "[abc".to_string(),
+        DirectoryStyle {
+            icon: Some("B".to_string()),
+            color: None,
+        },
+    );
+    let theme = Theme::new(&config);
+
+    assert_eq!(style(&theme, "src"), ("🧩".to_string(), red));
+    assert_eq!(style(&theme, "[abc").0, "B");
+    assert_eq!(style(&theme, "tests").0, "🧪");
+}
+
+#[test]
+fn chosen_directory_colors_win_over_named_ones() {
+    let mut config = create_config();
+    config.format.icon_set = Some(IconSet::Emoji);
+
+    let yellow = parse_sgr("01;33");
+    let theme = Theme::with_ls_colors(
+        &config,
+        &ThemeFile::default(),
+        LsColors::parse("di=01;33"),
+        LsColorsMode::Prefer,
+    );
+    assert_eq!(style(&theme, "src"), ("🧩".to_string(), yellow));
+    assert_eq!(style(&theme, "other").1, yellow);
+
+    let dracula = load_theme_file("dracula").unwrap();
+    let theme = Theme::with_file(&config, &dracula);
+    assert_eq!(style(&theme, "src").1, theme.element(Element::Directory));
+    assert_eq!(style(&theme, "src").1, dracula.elements["directory"]);
+}
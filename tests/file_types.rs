@@ -0,0 +1,310 @@
+use ls_enhanced::{
+    config::create_config,
+    types::{
+        files::{get_file_type, Category, TypeSelector, EXTENSION_TYPES},
+        icons::IconSet,
+        mime::{extension_mime, file_type_mime, mime_of},
+        sniff::sniff,
+    },
+    FileType,
+};
+use std::collections::HashSet;
+
+/// The type every built-in extension is meant to have
+const EXPECTED_EXTENSIONS: &[(FileType, &[&str])] = &[
+    (FileType::Rust, &["rs"]),
+    (FileType::C, &["c", "h"]),
+    (FileType::CPP, &["cpp", "cc", "cxx", "hpp"]),
+    (FileType::CS, &["cs"]),
+    (FileType::Zig, &["zig"]),
+    (FileType::Python, &["py", "pyi", "pyw"]),
+    (FileType::JavaScript, &["js", "mjs", "cjs"]),
+    (FileType::TypeScript, &["ts", "mts", "cts"]),
+    (FileType::Html, &["htm", "html"]),
+    (FileType::Css, &["css"]),
+    (FileType::Scss, &["scss", "sass"]),
+    (FileType::Less, &["less"]),
+    (FileType::React, &["jsx", "tsx"]),
+    (FileType::Lock, &["lock"]),
+    (FileType::Toml, &["toml"]),
+    (FileType::Markdown, &["md", "markdown", "mdx"]),
+    (FileType::Golang, &["go"]),
+    (FileType::Svg, &["svg"]),
+    (
+        FileType::Photo,
+        &[
+            "png", "jpg", "jpeg", "webp", "gif", "avif", "heic", "heif", "bmp", "tif", "tiff",
+            "ico",
+        ],
+    ),
+    (
+        FileType::Audio,
+        &[
+            "mp3", "wma", "wav", "voc", "tta", "opus", "mogg", "oga", "nmf", "mmf", "m4b", "m4a",
+            "iklax", "flac", "au", "aiff", "aax", "aac", "mp2", "f4a", "f4b",
+        ],
+    ),
+    (
+        FileType::Video,
+        &[
+            "webm", "mkv", "flv", "vob", "ogv", "ogg", "rrc", "gifv", "mng", "mov", "avi", "qt",
+            "wmv", "yuv", "rm", "asf", "amv", "mp4", "m4p", "mpg", "mpeg", "mpe", "mpv", "m4v",
+            "svi", "3gp", "3g2", "mxf", "roq", "nsv", "f4v", "f4p", "movpkg",
+        ],
+    ),
+    (FileType::Blender, &["blend"]),
+    (FileType::Lua, &["lua"]),
+    (FileType::Vim, &["vim"]),
+    (FileType::Gleam, &["gleam"]),
+    (FileType::Php, &["php"]),
+    (FileType::Json, &["json", "jsonc", "json5"]),
+    (FileType::Yaml, &["yml", "yaml"]),
+    (FileType::Kotlin, &["kot", "kt", "kts"]),
+    (
+        FileType::Java,
+        &["jar", "war", "ear", "aar", "java", "class"],
+    ),
+    (
+        FileType::Config,
+        &["properties", "ini", "conf", "cfg", "env"],
+    ),
+    (FileType::Assembly, &["asm", "s", "S"]),
+    (FileType::Twig, &["twig"]),
+    (FileType::Docker, &["dockerfile"]),
+    (FileType::Make, &["mk", "mak", "cmake"]),
+    (
+        FileType::Archive,
+        &[
+            "zip", "tar", "gz", "tgz", "xz", "bz2", "zst", "7z", "rar", "tbz2", "txz", "lz4",
+            "lzma", "deb", "rpm",
+        ],
+    ),
+    (FileType::Shell, &["sh", "bash", "zsh", "fish", "ksh"]),
+    (FileType::Pdf, &["pdf"]),
+    (FileType::Text, &["txt", "log"]),
+    (FileType::Ruby, &["rb", "erb", "gemspec", "rake", "ru"]),
+    (FileType::Swift, &["swift"]),
+    (FileType::Dart, &["dart"]),
+    (FileType::Elixir, &["ex", "exs", "eex", "heex"]),
+    (FileType::Erlang, &["erl", "hrl"]),
+    (FileType::Haskell, &["hs", "lhs"]),
+    (FileType::Scala, &["scala", "sc", "sbt"]),
+    (FileType::Perl, &["pl", "pm"]),
+    (FileType::R, &["r", "rmd"]),
+    (FileType::Julia, &["jl"]),
+    (FileType::OCaml, &["ml", "mli"]),
+    (FileType::Clojure, &["clj", "cljs", "cljc", "edn"]),
+    (FileType::Nim, &["nim", "nimble"]),
+    (FileType::Nix, &["nix"]),
+    (FileType::Terraform, &["tf", "tfvars", "hcl"]),
+    (FileType::Sql, &["sql"]),
+    (FileType::Protobuf, &["proto"]),
+    (FileType::GraphQL, &["graphql", "gql"]),
+    (FileType::Svelte, &["svelte"]),
+    (FileType::Vue, &["vue"]),
+    (FileType::Font, &["ttf", "otf", "woff", "woff2", "eot"]),
+    (FileType::Csv, &["csv", "tsv"]),
+    (FileType::Xml, &["xml", "xsd", "xsl", "plist"]),
+    (FileType::Tex, &["tex", "bib", "sty", "cls"]),
+    (FileType::Document, &["doc", "docx", "odt", "rtf"]),
+    (FileType::Spreadsheet, &["xls", "xlsx", "ods"]),
+    (FileType::Presentation, &["ppt", "pptx", "odp"]),
+    (FileType::Ebook, &["epub", "mobi", "azw3"]),
+    (FileType::Database, &["db", "sqlite", "sqlite3"]),
+    (FileType::Diff, &["diff", "patch"]),
+    (
+        FileType::Key,
+        &["pem", "crt", "cer", "key", "pub", "asc", "gpg", "p12"],
+    ),
+];
+
+/// Names that get their type from something else than their extension
+const EXPECTED_NAMES: &[(&str, FileType)] = &[
+    ("Cargo.toml", FileType::Rust),
+    ("package.json", FileType::JavaScript),
+    (".gitignore", FileType::Git),
+    (".vimrc", FileType::Vim),
+    (".bashrc", FileType::Config),
+    (".env", FileType::Config),
+    (".env.local", FileType::Config),
+    ("Makefile", FileType::Make),
+    ("makefile", FileType::Make),
+    ("CMakeLists.txt", FileType::Make),
+    ("Dockerfile", FileType::Docker),
+    ("Dockerfile.dev", FileType::Docker),
+    ("app.Dockerfile", FileType::Docker),
+    ("Gemfile", FileType::Ruby),
+    ("Rakefile", FileType::Ruby),
+    ("README", FileType::Markdown),
+    ("LICENSE-MIT", FileType::License),
+    ("archive.tar.gz", FileType::Archive),
+    ("types.d.ts", FileType::TypeScript),
+    ("bundle.min.js", FileType::JavaScript),
+    ("PHOTO.JPG", FileType::Photo),
+    ("notes", FileType::None),
+    ("data.xyz", FileType::Unknown),
+];
+
+#[test]
+fn every_extension_has_its_type() {
+    for (file_type, extensions) in EXPECTED_EXTENSIONS {
+        for extension in *extensions {
+            let name = format!("file.{}", extension);
+            assert_eq!(get_file_type(name.clone()), *file_type, "{}", name);
+        }
+    }
+}
+
+#[test]
+fn every_builtin_extension_is_expected() {
+    let expected: HashSet<&str> = EXPECTED_EXTENSIONS
+        .iter()
+        .flat_map(|(_, extensions)| extensions.iter().copied())
+        .collect();
+
+    for (extension, _, _) in EXTENSION_TYPES {
+        assert!(
+            expected.contains(extension),
+            "no expected type for .{}",
+            extension
+        );
+    }
+}
+
+#[test]
+fn no_extension_is_listed_twice() {
+    let mut seen = HashSet::new();
+
+    for (extension, _, _) in EXTENSION_TYPES {
+        assert!(seen.insert(extension), ".{} is listed twice", extension);
+    }
+}
+
+#[test]
+fn names_have_their_type() {
+    for (name, file_type) in EXPECTED_NAMES {
+        assert_eq!(get_file_type(name.to_string()), *file_type, "{}", name);
+    }
+}
+
+#[test]
+fn every_file_type_has_icons_and_a_color() {
+    let config = create_config();
+
+    for file_type in FileType::ALL {
+        if file_type == FileType::None {
+            continue;
+        }
+
+        let key = file_type.config_key();
+        assert!(config.colors.contains_key(key), "no color for {}", key);
+
+        for icon_set in [IconSet::Nerd, IconSet::Emoji, IconSet::Ascii] {
+            assert!(
+                !icon_set.glyph(file_type).is_empty(),
+                "no {:?} icon for {}",
+                icon_set,
+                key
+            );
+        }
+        assert_eq!(IconSet::Ascii.glyph(file_type).len(), 3, "{}", key);
+    }
+}
+
+#[test]
+fn config_keys_parse_back() {
+    for file_type in FileType::ALL {
+        assert_eq!(file_type.config_key().parse::<FileType>(), Ok(file_type));
+    }
+}
+
+#[test]
+fn every_category_has_file_types() {
+    for category in Category::ALL {
+        assert!(
+            FileType::ALL
+                .iter()
+                .any(|file_type| file_type.category() == category),
+            "{} is empty",
+            category.config_key()
+        );
+    }
+}
+
+#[test]
+fn selectors_match_categories_and_file_types() {
+    let media: TypeSelector = "media".parse().unwrap();
+    assert!(media.matches(FileType::Photo));
+    assert!(media.matches(FileType::Video));
+    assert!(!media.matches(FileType::Rust));
+
+    let rust: TypeSelector = "rust".parse().unwrap();
+    assert!(rust.matches(FileType::Rust));
+    assert!(!rust.matches(FileType::Golang));
+
+    assert!("nothing".parse::<TypeSelector>().is_err());
+}
+
+#[test]
+fn every_extension_has_a_mime_type_of_its_type() {
+    for (extension, file_type, _) in EXTENSION_TYPES {
+        let mime = extension_mime(extension).unwrap_or_else(|| panic!("no MIME for {}", extension));
+        assert_eq!(mime_of(&format!("file.{}", extension), file_type), mime);
+    }
+}
+
+#[test]
+fn mime_types_agree_with_the_category() {
+    for (extension, file_type, mime) in EXTENSION_TYPES {
+        let major = mime.split('/').next().unwrap();
+        let media_major = match file_type {
+            FileType::Photo | FileType::Svg => Some("image"),
+            FileType::Audio => Some("audio"),
+            FileType::Video => Some("video"),
+            FileType::Font => Some("font"),
+            _ => None,
+        };
+
+        // media in containers of their own are `application/*`, like `application/mxf`
+        let agrees = match media_major {
+            Some(media_major) => major == media_major || major == "application",
+            None => !["image", "audio", "video", "font"].contains(&major),
+        };
+        assert!(agrees, "{} is {:?} but {}", extension, file_type, mime);
+        assert_eq!(
+            file_type.category() == Category::Media,
+            media_major.is_some() || file_type == FileType::Blender,
+            "{:?}",
+            file_type
+        );
+    }
+}
+
+#[test]
+fn mime_types_follow_the_file_type() {
+    assert_eq!(mime_of("PHOTO.JPG", FileType::Photo), "image/jpeg");
+    // a rule that gives `*.ts` files another type changes their MIME type too
+    assert_eq!(
+        mime_of("video.ts", FileType::Video),
+        file_type_mime(FileType::Video)
+    );
+    assert_eq!(
+        mime_of("data.xyz", FileType::Unknown),
+        "application/octet-stream"
+    );
+
+    for file_type in FileType::ALL {
+        assert!(file_type_mime(file_type).contains('/'), "{:?}", file_type);
+    }
+}
+
+#[test]
+fn sniffed_files_have_a_mime_type() {
+    assert_eq!(
+        sniff(b"#!/usr/bin/env python3\n"),
+        Some((FileType::Python, "text/x-python"))
+    );
+    assert_eq!(sniff(b"%PDF-1.7"), Some((FileType::Pdf, "application/pdf")));
+    assert_eq!(sniff(b"plain text"), Some((FileType::Text, "text/plain")));
+    assert_eq!(sniff(b""), None);
+}
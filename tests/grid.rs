@@ -0,0 +1,68 @@
+use ls_enhanced::formatting::grid::{display_width, render_grid, Cell, GridDirection};
+
+fn cells(names: &[&str]) -> Vec<Cell> {
+    names.iter().map(|name| Cell::new(*name)).collect()
+}
+
+#[test]
+fn columns_are_filled_downwards_with_c() {
+    let cells = cells(&["a", "bb", "ccc", "dddd", "e"]);
+    // three columns would take 11 columns of the terminal
+    assert_eq!(
+        render_grid(&cells, 10, GridDirection::Down),
+        "a    dddd\nbb   e\nccc\n"
+    );
+}
+
+#[test]
+fn rows_are_filled_across_with_x() {
+    let cells = cells(&["a", "bb", "ccc", "dddd", "e"]);
+    assert_eq!(
+        render_grid(&cells, 10, GridDirection::Across),
+        "a    bb\nccc  dddd\ne\n"
+    );
+}
+
+#[test]
+fn everything_fits_on_one_line_when_wide_enough() {
+    let cells = cells(&["a", "bb", "ccc"]);
+    assert_eq!(render_grid(&cells, 80, GridDirection::Down), "a  bb  ccc\n");
+    assert_eq!(
+        render_grid(&cells, 80, GridDirection::Across),
+        "a  bb  ccc\n"
+    );
+}
+
+#[test]
+fn narrow_terminals_get_one_column() {
+    let cells = cells(&["alpha", "beta"]);
+    assert_eq!(render_grid(&cells, 3, GridDirection::Down), "alpha\nbeta\n");
+    assert_eq!(render_grid(&[], 80, GridDirection::Down), "");
+}
+
+#[test]
+fn wide_characters_take_two_columns() {
+    let cells = cells(&["日本", "ab", "cd"]);
+    assert_eq!(cells[0].width, 4);
+    assert_eq!(
+        render_grid(&cells, 8, GridDirection::Down),
+        "日本  cd\nab\n"
+    );
+    assert_eq!(
+        render_grid(&cells, 7, GridDirection::Down),
+        "日本\nab\ncd\n"
+    );
+}
+
+#[test]
+fn escape_sequences_take_no_room() {
+    assert_eq!(display_width("\x1b[1;31m日本\x1b[0m"), 4);
+    assert_eq!(
+        display_width("\x1b]8;;file:///tmp/name\x1b\\name\x1b]8;;\x1b\\"),
+        4
+    );
+    assert_eq!(
+        display_width("\x1b]8;;file:///tmp/name\x07name\x1b]8;;\x07"),
+        4
+    );
+}
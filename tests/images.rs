@@ -0,0 +1,214 @@
+mod common;
+
+use common::scratch;
+use ls_enhanced::{
+    config::create_config,
+    lister::SortKey,
+    types::image::{read_image_info, ImageInfo},
+    Errors, FileType, Lister, View,
+};
+use std::fs;
+
+/// Big-endian TIFF data with a single IFD of ASCII and SHORT fields, as found in EXIF segments
+fn tiff(ascii: &[(u16, &str)], shorts: &[(u16, u16)]) -> Vec<u8> {
+    let count = ascii.len() + shorts.len();
+    let mut data_offset = 8 + 2 + 12 * count + 4;
+    let mut fields = Vec::new();
+    let mut data = Vec::new();
+
+    for (tag, value) in ascii {
+        let value = format!("{}\0", value);
+        fields.extend(tag.to_be_bytes());
+        fields.extend(2u16.to_be_bytes());
+        fields.extend((value.len() as u32).to_be_bytes());
+        fields.extend((data_offset as u32).to_be_bytes());
+        data_offset += value.len();
+        data.extend(value.bytes());
+    }
+    for (tag, value) in shorts {
+        fields.extend(tag.to_be_bytes());
+        fields.extend(3u16.to_be_bytes());
+        fields.extend(1u32.to_be_bytes());
+        fields.extend(value.to_be_bytes());
+        fields.extend([0, 0]);
+    }
+
+    let mut tiff = b"MM\x00*\x00\x00\x00\x08".to_vec();
+    tiff.extend((count as u16).to_be_bytes());
+    tiff.extend(fields);
+    tiff.extend([0; 4]);
+    tiff.extend(data);
+    tiff
+}
+
+/// A JPEG file with a frame header and, optionally, an EXIF segment before it
+fn jpeg(width: u16, height: u16, exif: Option<Vec<u8>>) -> Vec<u8> {
+    let mut jpeg = b"\xff\xd8".to_vec();
+
+    if let Some(exif) = exif {
+        jpeg.extend(b"\xff\xe1");
+        jpeg.extend((exif.len() as u16 + 8).to_be_bytes());
+        jpeg.extend(b"Exif\x00\x00");
+        jpeg.extend(exif);
+    }
+
+    jpeg.extend(b"\xff\xc0\x00\x0b\x08");
+    jpeg.extend(height.to_be_bytes());
+    jpeg.extend(width.to_be_bytes());
+    jpeg.extend(b"\x01\x01\x11\x00");
+    jpeg.extend(b"\xff\xda\x00\x08\x01\x01\x00\x00\x3f\x00");
+    jpeg.extend([0; 64]);
+    jpeg.extend(b"\xff\xd9");
+    jpeg
+}
+
+/// An ISOBMFF box
+fn isobmff_box(kind: &[u8; 4], content: &[u8]) -> Vec<u8> {
+    let mut bytes = (content.len() as u32 + 8).to_be_bytes().to_vec();
+    bytes.extend(kind);
+    bytes.extend(content);
+    bytes
+}
+
+fn ispe(width: u32, height: u32) -> Vec<u8> {
+    let mut content = vec![0; 4];
+    content.extend(width.to_be_bytes());
+    content.extend(height.to_be_bytes());
+    isobmff_box(b"ispe", &content)
+}
+
+fn image_info(directory: &std::path::Path, name: &str, bytes: &[u8]) -> Option<ImageInfo> {
+    let path = directory.join(name);
+    fs::write(&path, bytes).unwrap();
+    let file_type = if name.ends_with(".svg") {
+        FileType::Svg
+    } else {
+        FileType::Photo
+    };
+    read_image_info(&path, file_type)
+}
+
+fn dimensions(info: Option<ImageInfo>) -> Option<(u32, u32)> {
+    info.and_then(|info| info.dimensions)
+}
+
+#[test]
+fn dimensions_come_from_the_headers() {
+    let directory = scratch("dimensions");
+
+    let mut png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+    png.extend(300u32.to_be_bytes());
+    png.extend(200u32.to_be_bytes());
+    png.extend([8, 2, 0, 0, 0]);
+    assert_eq!(
+        dimensions(image_info(&directory, "a.png", &png)),
+        Some((300, 200))
+    );
+
+    let gif = b"GIF89a\x20\x00\x10\x00\x00\x00\x00";
+    assert_eq!(
+        dimensions(image_info(&directory, "b.gif", gif)),
+        Some((32, 16))
+    );
+
+    let mut webp = b"RIFF\x1e\x00\x00\x00WEBPVP8X\x0a\x00\x00\x00\x00\x00\x00\x00".to_vec();
+    webp.extend(&799u32.to_le_bytes()[..3]);
+    webp.extend(&599u32.to_le_bytes()[..3]);
+    assert_eq!(
+        dimensions(image_info(&directory, "c.webp", &webp)),
+        Some((800, 600))
+    );
+
+    assert_eq!(
+        dimensions(image_info(&directory, "d.jpg", &jpeg(100, 200, None))),
+        Some((100, 200))
+    );
+
+    // the primary image is the largest of the `ispe` properties, not its thumbnail
+    let mut ipco = ispe(320, 240);
+    ipco.extend(ispe(4032, 3024));
+    let mut meta = vec![0; 4];
+    meta.extend(isobmff_box(b"iprp", &isobmff_box(b"ipco", &ipco)));
+    let mut heic = isobmff_box(b"ftyp", b"heic\x00\x00\x00\x00mif1heic");
+    heic.extend(isobmff_box(b"meta", &meta));
+    heic.extend(isobmff_box(b"mdat", &[0; 64]));
+    assert_eq!(
+        dimensions(image_info(&directory, "e.heic", &heic)),
+        Some((4032, 3024))
+    );
+
+    let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="100px" height="50">"#;
+    assert_eq!(
+        dimensions(image_info(&directory, "f.svg", svg.as_bytes())),
+        Some((100, 50))
+    );
+    let svg = r#"<?xml version="1.0"?><svg viewBox="0 0 24 16"></svg>"#;
+    assert_eq!(
+        dimensions(image_info(&directory, "g.svg", svg.as_bytes())),
+        Some((24, 16))
+    );
+
+    assert_eq!(image_info(&directory, "h.png", b"not a png"), None);
+}
+
+#[test]
+fn malformed_heif_files_have_no_dimensions() {
+    let directory = scratch("malformed_heif");
+
+    // a `free` box with a 64-bit size far past the end of the file
+    let mut heic = isobmff_box(b"ftyp", &[]);
+    heic.extend(1u32.to_be_bytes());
+    heic.extend(b"free");
+    heic.extend(0xffff_ffff_ffff_fff8u64.to_be_bytes());
+    heic.extend([0; 8]);
+    assert_eq!(dimensions(image_info(&directory, "a.heic", &heic)), None);
+}
+
+#[test]
+fn photos_have_their_exif_date_and_camera() {
+    let directory = scratch("exif");
+
+    // rotated by a quarter: the dimensions are swapped
+    let exif = tiff(
+        &[
+            (0x010f, "Canon"),
+            (0x0110, "Canon EOS R5"),
+            (0x0132, "2023:07:14 09:15:02"),
+        ],
+        &[(0x0112, 6)],
+    );
+    assert_eq!(
+        image_info(&directory, "a.jpg", &jpeg(640, 480, Some(exif))),
+        Some(ImageInfo {
+            dimensions: Some((480, 640)),
+            captured: Some("2023-07-14 09:15:02".to_string()),
+            camera: Some("Canon EOS R5".to_string()),
+        })
+    );
+
+    let exif = tiff(&[(0x010f, "Apple"), (0x0110, "iPhone 12")], &[]);
+    let info = image_info(&directory, "b.jpg", &jpeg(4032, 3024, Some(exif))).unwrap();
+    assert_eq!(info.dimensions, Some((4032, 3024)));
+    assert_eq!(info.captured, None);
+    assert_eq!(info.camera, Some("Apple iPhone 12".to_string()));
+}
+
+#[test]
+fn photos_sort_by_capture_date() {
+    let directory = scratch("captured");
+    let photo = |date: &str| jpeg(10, 10, Some(tiff(&[(0x0132, date)], &[])));
+    fs::write(directory.join("a.jpg"), photo("2024:01:01 00:00:00")).unwrap();
+    fs::write(directory.join("b.jpg"), photo("2020:06:01 12:00:00")).unwrap();
+    fs::write(directory.join("c.jpg"), jpeg(10, 10, None)).unwrap();
+    fs::write(directory.join("d.txt"), "notes").unwrap();
+
+    let mut out = Vec::new();
+    Lister::new(create_config())
+        .add_path(&directory)
+        .set_view(View::Zero)
+        .set_sort(SortKey::Captured)
+        .write_to(&mut out, &mut Errors::quiet())
+        .unwrap();
+
+    assert_eq!(out, b"b.jpg\0a.jpg\0c.jpg\0d.txt\0");
+}
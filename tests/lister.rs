@@ -0,0 +1,60 @@
+mod common;
+
+use common::scratch;
+use ls_enhanced::{
+    config::create_config,
+    formatting::grid::{display_width, GridDirection},
+    types::icons::IconSet,
+    Errors, Lister, View,
+};
+use std::fs;
+use std::os::unix::fs::symlink;
+
+#[test]
+fn only_views_showing_children_read_subdirectories() {
+    let directory = scratch("recursive");
+    fs::create_dir_all(directory.join("sub")).unwrap();
+    fs::write(directory.join("sub/a.rs"), "").unwrap();
+
+    let children = |view: View| {
+        let lister = Lister::new(create_config())
+            .set_view(view)
+            .set_recursive(true);
+        let root = lister
+            .collect_root(&directory, &mut Errors::quiet())
+            .unwrap();
+        root.children[0].children.len()
+    };
+
+    assert_eq!(children(View::Tree), 1);
+    assert_eq!(children(View::Json), 1);
+    assert_eq!(children(View::Grid(GridDirection::Down)), 0);
+    assert_eq!(children(View::Zero), 0);
+    assert_eq!(children(View::Long), 0);
+}
+
+#[test]
+fn symlinks_have_an_icon_like_other_entries() {
+    let directory = scratch("symlink_icons");
+    fs::write(directory.join("a.rs"), "").unwrap();
+    symlink("a.rs", directory.join("link")).unwrap();
+
+    let mut config = create_config();
+    config.format.icons = true;
+    config.format.icon_set = Some(IconSet::Emoji);
+    let mut out = Vec::new();
+    Lister::new(config)
+        .add_path(&directory)
+        .set_view(View::Long)
+        .set_show_permissions(true)
+        .write_to(&mut out, &mut Errors::quiet())
+        .unwrap();
+
+    // the names start in the same column
+    let out = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].ends_with("🔗 link"));
+    let name_column = |line: &str, name: &str| display_width(&line[..line.find(name).unwrap()]);
+    assert_eq!(name_column(lines[0], "a.rs"), name_column(lines[1], "link"));
+}
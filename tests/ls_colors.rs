@@ -0,0 +1,188 @@
+use ls_enhanced::{
+    config::{create_config, Color},
+    formatting::render::render_entry,
+    ls_colors::{parse_sgr, LsColors, LsColorsMode},
+    palette::ColorValue,
+    theme::{Element, Theme, ThemeFile},
+    types::{
+        entry::{Entry, EntryKind, Stat},
+        rules::FileTypeRules,
+    },
+};
+
+fn color(foreground: Option<ColorValue>, background: Option<ColorValue>, bold: bool) -> Color {
+    Color {
+        foreground,
+        background,
+        bold,
+        ..Color::default()
+    }
+}
+
+#[test]
+fn sgr_parameters_become_colors() {
+    assert_eq!(
+        parse_sgr("01;38;5;208"),
+        color(Some(ColorValue::Fixed(208)), None, true)
+    );
+    assert_eq!(
+        parse_sgr("38;2;1;2;3;48;5;17"),
+        color(
+            Some(ColorValue::Rgb(1, 2, 3)),
+            Some(ColorValue::Fixed(17)),
+            false
+        )
+    );
+    assert_eq!(
+        parse_sgr("93;101"),
+        color(Some(ColorValue::Ansi(11)), Some(ColorValue::Ansi(9)), false)
+    );
+
+    let underlined = parse_sgr("4;3");
+    assert!(underlined.underline && underlined.italic);
+}
+
+#[test]
+fn invalid_sgr_codes_are_skipped() {
+    let red = color(Some(ColorValue::Ansi(1)), None, true);
+    assert_eq!(parse_sgr("01;999;31"), red);
+    assert_eq!(parse_sgr("01;x;31"), red);
+    assert_eq!(parse_sgr("01;38;5;300;31"), red);
+
+    // unlike invalid codes, 0 and empty ones are resets
+    assert_eq!(
+        parse_sgr("01;0;31"),
+        color(Some(ColorValue::Ansi(1)), None, false)
+    );
+    assert_eq!(
+        parse_sgr("01;;31"),
+        color(Some(ColorValue::Ansi(1)), None, false)
+    );
+}
+
+#[test]
+fn ls_colors_values_are_read() {
+    let ls_colors = LsColors::parse("di=01;34:ex=01;32:*.gz=31:*.TAR.GZ=35:bad:*.md=");
+
+    assert_eq!(
+        ls_colors.kind("di"),
+        Some(&color(Some(ColorValue::Ansi(4)), None, true))
+    );
+    // the longest suffix wins, ignoring case
+    assert_eq!(
+        ls_colors.suffix_color("backup.tar.gz"),
+        Some(&Color::from(ColorValue::Ansi(5)))
+    );
+    assert_eq!(
+        ls_colors.suffix_color("LOG.GZ"),
+        Some(&Color::from(ColorValue::Ansi(1)))
+    );
+    assert_eq!(ls_colors.suffix_color("notes.md"), Some(&Color::default()));
+    assert_eq!(ls_colors.suffix_color("notes.txt"), None);
+}
+
+#[test]
+fn dircolors_databases_are_read() {
+    let ls_colors = LsColors::parse_dircolors(
+        "# Configuration file for dircolors\n\
+         TERM xterm-256color\n\
+         COLOR tty\n\
+         DIR 01;34 # directories\n\
+         link 01;36\n\
+         EXEC 01;32\n\
+         .tar 01;31\n\
+         *README 33\n",
+    );
+
+    assert_eq!(
+        ls_colors.kind("di"),
+        Some(&color(Some(ColorValue::Ansi(4)), None, true))
+    );
+    assert_eq!(
+        ls_colors.kind("ln"),
+        Some(&color(Some(ColorValue::Ansi(6)), None, true))
+    );
+    assert!(ls_colors.kind("ex").is_some());
+    assert_eq!(ls_colors.kinds.len(), 3);
+    assert_eq!(
+        ls_colors.suffix_color("a.tar"),
+        Some(&color(Some(ColorValue::Ansi(1)), None, true))
+    );
+    assert_eq!(
+        ls_colors.suffix_color("README"),
+        Some(&Color::from(ColorValue::Ansi(3)))
+    );
+}
+
+#[test]
+fn ls_colors_apply_without_icons() {
+    let mut config = create_config();
+    config.format.icons = false;
+    config.format.colors = true;
+
+    let theme = Theme::with_ls_colors(
+        &config,
+        &ThemeFile::default(),
+        LsColors::parse("di=01;31:ln=35"),
+        LsColorsMode::Prefer,
+    );
+    assert_eq!(
+        theme.element(Element::Directory),
+        color(Some(ColorValue::Ansi(1)), None, true)
+    );
+    assert_eq!(
+        theme.element(Element::Symlink),
+        Color::from(ColorValue::Ansi(5))
+    );
+
+    // but not without colors
+    config.format.colors = false;
+    let theme = Theme::with_ls_colors(
+        &config,
+        &ThemeFile::default(),
+        LsColors::parse("di=01;31"),
+        LsColorsMode::Prefer,
+    );
+    assert_eq!(
+        theme.element(Element::Directory),
+        Element::Directory.default_color()
+    );
+}
+
+fn file(name: &str, mode: u32) -> Entry {
+    let stat = Stat {
+        size: 0,
+        mode: mode | EntryKind::File.mode_bits(),
+        modified: None,
+    };
+    Entry::with_stat(name, name, EntryKind::File, stat, FileTypeRules::builtin())
+}
+
+#[test]
+fn ls_colors_color_names_without_icons() {
+    colored::control::set_override(true);
+    let mut config = create_config();
+    config.format.icons = false;
+    config.format.colors = true;
+
+    let theme = Theme::with_ls_colors(
+        &config,
+        &ThemeFile::default(),
+        LsColors::parse("ex=01;32:*.xyz=35"),
+        LsColorsMode::Prefer,
+    );
+
+    let notes = file("notes.xyz", 0o644);
+    assert_eq!(
+        theme.file_color(&notes),
+        Some(Color::from(ColorValue::Ansi(5)))
+    );
+    assert_eq!(
+        render_entry(&config, &theme, &notes),
+        "\x1b[35mnotes.xyz\x1b[0m"
+    );
+    assert_eq!(
+        render_entry(&config, &theme, &file("run", 0o755)),
+        "\x1b[1;32mrun\x1b[0m"
+    );
+}
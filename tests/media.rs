@@ -0,0 +1,312 @@
+mod common;
+
+use common::scratch;
+use ls_enhanced::types::media::{read_media_info, MediaInfo};
+use std::fs;
+
+fn media_info(test: &str, name: &str, bytes: &[u8]) -> Option<MediaInfo> {
+    let path = scratch(test).join(name);
+    fs::write(&path, bytes).unwrap();
+    read_media_info(&path)
+}
+
+/// A RIFF chunk or an MP4 box
+fn chunk(kind: &[u8; 4], content: &[u8], little_endian: bool) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    if little_endian {
+        bytes.extend(kind);
+        bytes.extend((content.len() as u32).to_le_bytes());
+    } else {
+        bytes.extend((content.len() as u32 + 8).to_be_bytes());
+        bytes.extend(kind);
+    }
+    bytes.extend(content);
+    bytes
+}
+
+fn mp4_box(kind: &[u8; 4], children: &[Vec<u8>]) -> Vec<u8> {
+    chunk(kind, &children.concat(), false)
+}
+
+/// An EBML element with a size that fits in one byte, or an unknown size
+fn ebml(id: &[u8], content: &[u8], known_size: bool) -> Vec<u8> {
+    let mut bytes = id.to_vec();
+    if known_size {
+        bytes.push(0x80 | content.len() as u8);
+    } else {
+        bytes.push(0xff);
+    }
+    bytes.extend(content);
+    bytes
+}
+
+#[test]
+fn wav_files_have_their_format() {
+    let mut format = Vec::new();
+    format.extend(1u16.to_le_bytes());
+    format.extend(2u16.to_le_bytes());
+    format.extend(44_100u32.to_le_bytes());
+    format.extend(176_400u32.to_le_bytes());
+    format.extend(4u16.to_le_bytes());
+    format.extend(16u16.to_le_bytes());
+
+    let mut wave = b"WAVE".to_vec();
+    wave.extend(chunk(b"fmt ", &format, true));
+    // odd chunks are padded
+    wave.extend(chunk(b"LIST", b"abc", true));
+    wave.push(0);
+    wave.extend(b"data");
+    wave.extend((176_400u32 * 2).to_le_bytes());
+    let wav = chunk(b"RIFF", &wave, true);
+
+    assert_eq!(
+        media_info("wav", "a.wav", &wav),
+        Some(MediaInfo {
+            duration: Some(2.0),
+            bitrate: Some(1_411_200),
+            resolution: None,
+            container: "wav",
+            codec: Some("pcm".to_string()),
+        })
+    );
+}
+
+#[test]
+fn corrupt_wav_byte_rates_are_not_trusted() {
+    let mut format = Vec::new();
+    format.extend(1u16.to_le_bytes());
+    format.extend(2u16.to_le_bytes());
+    format.extend(44_100u32.to_le_bytes());
+    format.extend(u32::MAX.to_le_bytes());
+    format.extend(4u16.to_le_bytes());
+    format.extend(16u16.to_le_bytes());
+
+    let mut wave = b"WAVE".to_vec();
+    wave.extend(chunk(b"fmt ", &format, true));
+    wave.extend(chunk(b"data", &[0; 64], true));
+    let wav = chunk(b"RIFF", &wave, true);
+
+    let info = media_info("corrupt_wav", "a.wav", &wav).unwrap();
+    let duration = info.duration.unwrap();
+    assert_eq!(
+        info.bitrate,
+        Some((wav.len() as f64 * 8.0 / duration) as u32)
+    );
+}
+
+#[test]
+fn flac_files_have_their_duration() {
+    // 48 kHz, stereo, 16 bits, 3 seconds of samples
+    let streaminfo: u64 = (48_000 << 44) | (1 << 41) | (15 << 36) | (48_000 * 3);
+    let mut flac = b"fLaC\x80\x00\x00\x22".to_vec();
+    flac.extend([0; 10]);
+    flac.extend(streaminfo.to_be_bytes());
+    flac.extend([0; 16]);
+
+    let info = media_info("flac", "a.flac", &flac).unwrap();
+    assert_eq!(info.duration, Some(3.0));
+    assert_eq!(info.container, "flac");
+    assert!(info.bitrate.is_some());
+}
+
+#[test]
+fn mp3_files_have_their_bitrate() {
+    // MPEG-1 layer III, 128 kb/s, 44.1 kHz: 417 bytes per frame
+    let frame = |content: &[u8]| {
+        let mut frame = b"\xff\xfb\x90\x00".to_vec();
+        frame.extend(content);
+        frame.resize(417, 0);
+        frame
+    };
+
+    let mut mp3 = b"ID3\x04\x00\x00\x00\x00\x00\x20".to_vec();
+    mp3.extend([0; 32]);
+    mp3.extend((0..10).flat_map(|_| frame(&[])));
+    let info = media_info("mp3", "cbr.mp3", &mp3).unwrap();
+    assert_eq!(info.bitrate, Some(128_000));
+    assert_eq!(info.duration, Some(4170.0 * 8.0 / 128_000.0));
+    assert_eq!(info.codec, Some("mp3".to_string()));
+
+    // a Xing header in the first frame, after 32 bytes of side information
+    let mut xing = vec![0; 32];
+    xing.extend(b"Xing");
+    xing.extend(3u32.to_be_bytes());
+    xing.extend(100u32.to_be_bytes());
+    xing.extend(41_700u32.to_be_bytes());
+    let mut mp3 = frame(&xing);
+    mp3.extend((0..10).flat_map(|_| frame(&[])));
+    let info = media_info("vbr", "vbr.mp3", &mp3).unwrap();
+    assert_eq!(info.duration, Some(100.0 * 1152.0 / 44_100.0));
+    assert_eq!(
+        info.bitrate,
+        Some((41_700.0 * 8.0 / (100.0 * 1152.0 / 44_100.0)) as u32)
+    );
+}
+
+#[test]
+fn mp4_files_have_their_tracks() {
+    let mut mvhd = vec![0; 12];
+    mvhd.extend(1_000u32.to_be_bytes());
+    mvhd.extend(5_000u32.to_be_bytes());
+    mvhd.resize(100, 0);
+
+    let track = |handler: &[u8; 4], format: &[u8; 4], width: u32, height: u32| {
+        let mut tkhd = vec![0; 76];
+        tkhd.extend((width << 16).to_be_bytes());
+        tkhd.extend((height << 16).to_be_bytes());
+        let mut hdlr = vec![0; 8];
+        hdlr.extend(handler);
+        hdlr.extend([0; 12]);
+        let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 16];
+        stsd.extend(format);
+        stsd.extend([0; 8]);
+
+        mp4_box(
+            b"trak",
+            &[
+                chunk(b"tkhd", &tkhd, false),
+                mp4_box(
+                    b"mdia",
+                    &[
+                        chunk(b"hdlr", &hdlr, false),
+                        mp4_box(
+                            b"minf",
+                            &[mp4_box(b"stbl", &[chunk(b"stsd", &stsd, false)])],
+                        ),
+                    ],
+                ),
+            ],
+        )
+    };
+
+    // the `moov` box after the data
+    let mut mp4 = chunk(b"ftyp", b"isom\x00\x00\x02\x00isomiso2", false);
+    mp4.extend(chunk(b"mdat", &[0; 256], false));
+    mp4.extend(mp4_box(
+        b"moov",
+        &[
+            chunk(b"mvhd", &mvhd, false),
+            track(b"soun", b"mp4a", 0, 0),
+            track(b"vide", b"avc1", 1920, 1080),
+        ],
+    ));
+
+    assert_eq!(
+        media_info("mp4", "a.mp4", &mp4),
+        Some(MediaInfo {
+            duration: Some(5.0),
+            bitrate: None,
+            resolution: Some((1920, 1080)),
+            container: "mp4",
+            codec: Some("h264".to_string()),
+        })
+    );
+}
+
+#[test]
+fn matroska_files_have_their_tracks() {
+    let video = [
+        ebml(&[0xb0], &1280u16.to_be_bytes(), true),
+        ebml(&[0xba], &720u16.to_be_bytes(), true),
+    ]
+    .concat();
+    let track_entry = [
+        ebml(&[0x83], &[1], true),
+        ebml(&[0x86], b"V_VP9", true),
+        ebml(&[0xe0], &video, true),
+    ]
+    .concat();
+    let info = [
+        ebml(&[0x2a, 0xd7, 0xb1], &[0x0f, 0x42, 0x40], true),
+        ebml(&[0x44, 0x89], &12_345f64.to_be_bytes(), true),
+    ]
+    .concat();
+    let segment = [
+        ebml(&[0x15, 0x49, 0xa9, 0x66], &info, true),
+        ebml(
+            &[0x16, 0x54, 0xae, 0x6b],
+            &ebml(&[0xae], &track_entry, true),
+            true,
+        ),
+        ebml(&[0x1f, 0x43, 0xb6, 0x75], &[0; 32], false),
+    ]
+    .concat();
+
+    let mut webm = ebml(
+        &[0x1a, 0x45, 0xdf, 0xa3],
+        &ebml(&[0x42, 0x82], b"webm", true),
+        true,
+    );
+    webm.extend(ebml(&[0x18, 0x53, 0x80, 0x67], &segment, false));
+
+    assert_eq!(
+        media_info("webm", "a.webm", &webm),
+        Some(MediaInfo {
+            duration: Some(12.345),
+            bitrate: None,
+            resolution: Some((1280, 720)),
+            container: "webm",
+            codec: Some("vp9".to_string()),
+        })
+    );
+}
+
+#[test]
+fn malformed_mp4_files_do_not_panic() {
+    // a box with a 64-bit size that wraps around the offset
+    let mut mp4 = chunk(b"ftyp", b"isom", false);
+    mp4.extend(1u32.to_be_bytes());
+    mp4.extend(b"free");
+    mp4.extend((u64::MAX - 4).to_be_bytes());
+    let info = media_info("malformed_mp4", "a.mp4", &mp4).unwrap();
+    assert_eq!(info.duration, None);
+    assert_eq!(info.resolution, None);
+
+    // a 64-bit size header cut by the end of the file
+    let mut moov = 8u32.to_be_bytes().to_vec();
+    moov.extend(b"free");
+    moov.extend(1u32.to_be_bytes());
+    moov.extend(b"trak");
+    let mut mp4 = chunk(b"ftyp", b"isom", false);
+    mp4.extend(chunk(b"moov", &moov, false));
+    assert_eq!(media_info("malformed_mp4", "b.mp4", &mp4), None);
+}
+
+#[test]
+fn malformed_matroska_files_do_not_panic() {
+    // the header of the `DocType` goes past the end of the EBML header
+    let mut mkv = vec![0x1a, 0x45, 0xdf, 0xa3, 0x82, 0x42, 0x82];
+    mkv.extend([0x40, 0x08]);
+    mkv.extend(b"webm\x00\x00\x00");
+    let info = media_info("malformed_mkv", "a.mkv", &mkv).unwrap();
+    assert_eq!(info.container, "mkv");
+
+    // a track type with a size far past the end of the file, in a truncated segment
+    let track_entry = [0x83, 0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe];
+    let tracks = ebml(&[0xae], &track_entry, true);
+    let mut mkv = ebml(&[0x1a, 0x45, 0xdf, 0xa3], &[], true);
+    mkv.extend(ebml(
+        &[0x18, 0x53, 0x80, 0x67],
+        &ebml(&[0x16, 0x54, 0xae, 0x6b], &tracks, true),
+        true,
+    ));
+    let info = media_info("malformed_mkv", "b.mkv", &mkv).unwrap();
+    assert_eq!(info.duration, None);
+    assert_eq!(info.resolution, None);
+
+    // a `DocType` with a size far past the end of the file, in a header of unknown size
+    let mut mkv = vec![0x1a, 0x45, 0xdf, 0xa3, 0xff, 0x42, 0x82];
+    mkv.extend([0x01, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff]);
+    mkv.extend(b"webm");
+    mkv.resize(1 << 20, 0);
+    let info = media_info("malformed_mkv", "c.mkv", &mkv).unwrap();
+    assert_eq!(info.container, "mkv");
+}
+
+#[test]
+fn other_files_have_no_media_info() {
+    assert_eq!(
+        media_info("other", "a.ogg", b"OggS\x00\x02\x00\x00\x00\x00\x00\x00"),
+        None
+    );
+}
@@ -0,0 +1,76 @@
+use ls_enhanced::formatting::quoting::{quote_name, QuotingStyle};
+use std::ffi::OsStr;
+use std::io::{self, IsTerminal};
+use std::os::unix::ffi::OsStrExt;
+
+fn quote(name: &[u8], style: QuotingStyle) -> String {
+    quote_name(OsStr::from_bytes(name), style)
+}
+
+#[test]
+fn styles_parse_from_their_gnu_names() {
+    assert_eq!("shell-escape".parse(), Ok(QuotingStyle::ShellEscape));
+    assert_eq!("c".parse(), Ok(QuotingStyle::C));
+    assert!("locale".parse::<QuotingStyle>().is_err());
+}
+
+#[test]
+fn literal_prints_names_as_they_are() {
+    assert_eq!(quote(b"a b'c\"", QuotingStyle::Literal), "a b'c\"");
+    // one `?` per invalid byte, not U+FFFD
+    assert_eq!(quote(b"a\xff\xfeb", QuotingStyle::Literal), "a??b");
+
+    let control = if io::stdout().is_terminal() {
+        "a?b"
+    } else {
+        "a\nb"
+    };
+    assert_eq!(quote(b"a\nb", QuotingStyle::Literal), control);
+}
+
+#[test]
+fn shell_quotes_only_when_needed() {
+    assert_eq!(quote(b"plain.txt", QuotingStyle::Shell), "plain.txt");
+    assert_eq!(quote(b"a b", QuotingStyle::Shell), "'a b'");
+    assert_eq!(quote(b"~home", QuotingStyle::Shell), "'~home'");
+    assert_eq!(quote(b"a~b", QuotingStyle::Shell), "a~b");
+    assert_eq!(quote(b"it's", QuotingStyle::Shell), "\"it's\"");
+    assert_eq!(quote(b"it's $5", QuotingStyle::Shell), "'it'\\''s $5'");
+    assert_eq!(quote(b"", QuotingStyle::Shell), "''");
+    assert_eq!(quote(b"a\nb", QuotingStyle::Shell), "'a?b'");
+    assert_eq!(quote(b"a\xffb", QuotingStyle::Shell), "'a?b'");
+}
+
+#[test]
+fn shell_escape_spells_out_control_characters_and_bytes() {
+    assert_eq!(quote(b"plain.txt", QuotingStyle::ShellEscape), "plain.txt");
+    assert_eq!(quote(b"a b", QuotingStyle::ShellEscape), "'a b'");
+    assert_eq!(quote(b"a\nb", QuotingStyle::ShellEscape), "'a'$'\\n''b'");
+    assert_eq!(quote(b"\x01\t", QuotingStyle::ShellEscape), "$'\\001\\t'");
+    assert_eq!(
+        quote(b"a\xffb", QuotingStyle::ShellEscape),
+        "'a'$'\\377''b'"
+    );
+}
+
+#[test]
+fn c_quotes_like_a_string_literal() {
+    assert_eq!(quote(b"plain", QuotingStyle::C), "\"plain\"");
+    assert_eq!(quote(b"a b", QuotingStyle::C), "\"a b\"");
+    assert_eq!(quote(b"say \"hi\"", QuotingStyle::C), "\"say \\\"hi\\\"\"");
+    assert_eq!(quote(b"a\\b", QuotingStyle::C), "\"a\\\\b\"");
+    assert_eq!(
+        quote(b"\x07\x08\x0c\n\r\t\x0b", QuotingStyle::C),
+        "\"\\a\\b\\f\\n\\r\\t\\v\""
+    );
+    assert_eq!(quote(b"\x1b\xff", QuotingStyle::C), "\"\\033\\377\"");
+    assert_eq!(quote("é".as_bytes(), QuotingStyle::C), "\"é\"");
+}
+
+#[test]
+fn escape_is_c_without_the_quotes() {
+    assert_eq!(quote(b"plain", QuotingStyle::Escape), "plain");
+    assert_eq!(quote(b"a b", QuotingStyle::Escape), "a\\ b");
+    assert_eq!(quote(b"say \"hi\"", QuotingStyle::Escape), "say\\ \"hi\"");
+    assert_eq!(quote(b"a\nb\xff", QuotingStyle::Escape), "a\\nb\\377");
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::scratch;
+use ls_enhanced::{
+    types::{rules::FileTypeRules, sniff::sniff},
+    FileType,
+};
+use std::fs;
+
+fn sniffed_type(bytes: &[u8]) -> Option<FileType> {
+    sniff(bytes).map(|(file_type, _)| file_type)
+}
+
+#[test]
+fn shebangs_give_the_interpreter() {
+    assert_eq!(
+        sniffed_type(b"#!/bin/bash\necho hi\n"),
+        Some(FileType::Shell)
+    );
+    assert_eq!(sniffed_type(b"#!/bin/sh"), Some(FileType::Shell));
+    assert_eq!(
+        sniffed_type(b"#!/usr/bin/env -S node --no-warnings\n"),
+        Some(FileType::JavaScript)
+    );
+    // variables given to env and versions are left out
+    assert_eq!(
+        sniffed_type(b"#!/usr/bin/env PYTHONPATH=. python3.12\n"),
+        Some(FileType::Python)
+    );
+    assert_eq!(
+        sniffed_type(b"#!/usr/local/bin/lua5.4\n"),
+        Some(FileType::Lua)
+    );
+    assert_eq!(sniffed_type(b"#!/usr/bin/perl\n"), Some(FileType::Text));
+}
+
+#[test]
+fn magic_numbers_give_the_format() {
+    assert_eq!(
+        sniff(b"\x7fELF\x02\x01\x01\x00"),
+        Some((FileType::Binary, "application/x-executable"))
+    );
+    assert_eq!(
+        sniff(b"\xcf\xfa\xed\xfe\x07\x00\x00\x01"),
+        Some((FileType::Binary, "application/x-mach-binary"))
+    );
+    // two architectures, or the version of Java 8
+    assert_eq!(
+        sniff(b"\xca\xfe\xba\xbe\x00\x00\x00\x02"),
+        Some((FileType::Binary, "application/x-mach-binary"))
+    );
+    assert_eq!(
+        sniff(b"\xca\xfe\xba\xbe\x00\x00\x00\x34"),
+        Some((FileType::Java, "application/java-vm"))
+    );
+    assert_eq!(
+        sniff(b"\x89PNG\r\n\x1a\n\x00\x00"),
+        Some((FileType::Photo, "image/png"))
+    );
+    assert_eq!(
+        sniff(b"\x28\xb5\x2f\xfd\x00"),
+        Some((FileType::Archive, "application/zstd"))
+    );
+
+    let mut tar = vec![0; 512];
+    tar[..8].copy_from_slice(b"file.txt");
+    tar[257..263].copy_from_slice(b"ustar\x00");
+    assert_eq!(sniff(&tar), Some((FileType::Archive, "application/x-tar")));
+}
+
+#[test]
+fn text_is_told_from_binary() {
+    assert_eq!(sniffed_type(b"just some notes\n"), Some(FileType::Text));
+    assert_eq!(sniffed_type("café".as_bytes()), Some(FileType::Text));
+    // a character cut by the end of the bytes read
+    assert_eq!(sniffed_type(b"caf\xc3"), Some(FileType::Text));
+    assert_eq!(sniffed_type(b"caf\xff\xfe"), Some(FileType::Binary));
+    assert_eq!(sniffed_type(b"text\x00with a nul"), Some(FileType::Binary));
+    assert_eq!(sniff(b""), None);
+}
+
+#[test]
+fn only_files_without_a_known_name_are_sniffed() {
+    let directory = scratch("sniff");
+    fs::write(directory.join("run"), "#!/bin/sh\n").unwrap();
+    fs::write(directory.join("image.rs"), b"\x89PNG\r\n\x1a\n").unwrap();
+    fs::write(directory.join("empty"), "").unwrap();
+
+    let rules = FileTypeRules::new(&[]).set_sniff(true);
+    let file_type = |name: &str| rules.file_type_at(name, &directory.join(name)).0;
+    assert_eq!(file_type("run"), FileType::Shell);
+    assert_eq!(file_type("image.rs"), FileType::Rust);
+    assert_eq!(file_type("empty"), FileType::None);
+    assert_eq!(file_type("missing"), FileType::None);
+
+    let rules = FileTypeRules::new(&[]);
+    assert_eq!(
+        rules.file_type_at("run", &directory.join("run")).0,
+        FileType::None
+    );
+}
@@ -0,0 +1,29 @@
+use ls_enhanced::{
+    theme::{builtin_theme_names, load_theme_file, Element},
+    FileType,
+};
+
+#[test]
+fn builtin_themes_color_every_file_type_and_element() {
+    for name in builtin_theme_names() {
+        let theme = load_theme_file(name).unwrap();
+
+        for file_type in FileType::ALL {
+            let key = file_type.config_key();
+            assert!(
+                file_type == FileType::None || theme.colors.contains_key(key),
+                "no color for {} in {}",
+                key,
+                name
+            );
+        }
+        for element in Element::ALL {
+            assert!(
+                theme.elements.contains_key(element.key()),
+                "no color for {} in {}",
+                element.key(),
+                name
+            );
+        }
+    }
+}